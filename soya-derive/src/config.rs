@@ -6,14 +6,17 @@ use syn::parse::ParseStream;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::Attribute;
+use syn::Meta;
 use syn::Token;
 
 use crate::error;
 use crate::value::Value;
 
 pub mod arg;
+pub mod infer;
 pub mod soya;
 pub mod sub;
+pub mod value;
 
 pub trait ArgParser
 where
@@ -94,7 +97,10 @@ where
 
 impl<T: ArgParser> Configs<T> {
     pub fn parse_attrs(name: &str, attrs: &[Attribute]) -> Self {
-        let attrs = attrs.iter().filter(|v| v.path().is_ident(name));
+        // the attribute without configurations, such as `#[pos]`
+        let attrs = attrs
+            .iter()
+            .filter(|v| v.path().is_ident(name) && !matches!(v.meta, Meta::Path(_)));
         let cfgs = attrs.map(|attr| {
             attr.parse_args_with(Punctuated::<Config<T>, Token![,]>::parse_terminated)
                 .map(|res| res.into_iter())
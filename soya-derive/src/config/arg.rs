@@ -1,11 +1,31 @@
-use quote::ToTokens;
 use syn::Path;
 
 use super::ArgParser;
+use crate::error;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Argument {
-    Policy,
+    Name,
+
+    Alias,
+
+    Index,
+
+    Validate,
+
+    ConflictsWith,
+
+    Requires,
+
+    RequiredIf,
+
+    Group,
+
+    Hide,
+
+    Complete,
+
+    Env,
 }
 
 impl ArgParser for Argument {
@@ -16,17 +36,26 @@ impl ArgParser for Argument {
             let arg = ident.to_string();
 
             Ok(match arg.as_str() {
-                "policy" => (Self::Policy, true),
+                "name" => (Self::Name, true),
+                "alias" => (Self::Alias, true),
+                "index" => (Self::Index, true),
+                "validate" => (Self::Validate, true),
+                "conflicts_with" => (Self::ConflictsWith, true),
+                "requires" => (Self::Requires, true),
+                "required_if" => (Self::RequiredIf, true),
+                "group" => (Self::Group, true),
+                "hide" => (Self::Hide, false),
+                "complete" => (Self::Complete, true),
+                "env" => (Self::Env, true),
                 _ => {
-                    unimplemented!()
+                    return Err(error(
+                        ident,
+                        format!("unknown `arg` configuration `{}`", arg),
+                    ));
                 }
             })
         } else {
-            // let method = path.to_token_stream().to_string();
-            // let method = method.replace(char::is_whitespace, "");
-
-            // Ok((Self::MethodCall(method), true))
-            unimplemented!()
+            Err(error(path, "invalid `arg` configuration"))
         }
     }
 }
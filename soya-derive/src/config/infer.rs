@@ -0,0 +1,34 @@
+use syn::Path;
+
+use super::ArgParser;
+use crate::error;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Argument {
+    FromStr,
+
+    TryFrom,
+}
+
+impl ArgParser for Argument {
+    fn parse(input: &mut syn::parse::ParseStream) -> syn::Result<(Self, bool)> {
+        let path: Path = input.parse()?;
+
+        if let Some(ident) = path.get_ident() {
+            let arg = ident.to_string();
+
+            Ok(match arg.as_str() {
+                "from_str" => (Self::FromStr, false),
+                "try_from" => (Self::TryFrom, false),
+                _ => {
+                    return Err(error(
+                        ident,
+                        format!("unknown `infer` configuration `{}`", arg),
+                    ));
+                }
+            })
+        } else {
+            Err(error(path, "invalid `infer` configuration"))
+        }
+    }
+}
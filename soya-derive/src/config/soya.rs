@@ -1,11 +1,15 @@
-use quote::ToTokens;
 use syn::Path;
 
 use super::ArgParser;
+use crate::error;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Argument {
     Policy,
+
+    Validate,
+
+    Group,
 }
 
 impl ArgParser for Argument {
@@ -17,16 +21,17 @@ impl ArgParser for Argument {
 
             Ok(match arg.as_str() {
                 "policy" => (Self::Policy, true),
+                "validate" => (Self::Validate, true),
+                "group" => (Self::Group, true),
                 _ => {
-                    unimplemented!()
+                    return Err(error(
+                        ident,
+                        format!("unknown `soya` configuration `{}`", arg),
+                    ));
                 }
             })
         } else {
-            // let method = path.to_token_stream().to_string();
-            // let method = method.replace(char::is_whitespace, "");
-
-            // Ok((Self::MethodCall(method), true))
-            unimplemented!()
+            Err(error(path, "invalid `soya` configuration"))
         }
     }
 }
@@ -1,11 +1,13 @@
-use quote::ToTokens;
 use syn::Path;
 
 use super::ArgParser;
+use crate::error;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Argument {
-    Policy,
+    Name,
+
+    Alias,
 }
 
 impl ArgParser for Argument {
@@ -16,17 +18,17 @@ impl ArgParser for Argument {
             let arg = ident.to_string();
 
             Ok(match arg.as_str() {
-                "policy" => (Self::Policy, true),
+                "name" => (Self::Name, true),
+                "alias" => (Self::Alias, true),
                 _ => {
-                    unimplemented!()
+                    return Err(error(
+                        ident,
+                        format!("unknown `sub` configuration `{}`", arg),
+                    ));
                 }
             })
         } else {
-            // let method = path.to_token_stream().to_string();
-            // let method = method.replace(char::is_whitespace, "");
-
-            // Ok((Self::MethodCall(method), true))
-            unimplemented!()
+            Err(error(path, "invalid `sub` configuration"))
         }
     }
 }
@@ -0,0 +1,34 @@
+use syn::Path;
+
+use super::ArgParser;
+use crate::error;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Argument {
+    Name,
+
+    Alias,
+}
+
+impl ArgParser for Argument {
+    fn parse(input: &mut syn::parse::ParseStream) -> syn::Result<(Self, bool)> {
+        let path: Path = input.parse()?;
+
+        if let Some(ident) = path.get_ident() {
+            let arg = ident.to_string();
+
+            Ok(match arg.as_str() {
+                "name" => (Self::Name, true),
+                "alias" => (Self::Alias, true),
+                _ => {
+                    return Err(error(
+                        ident,
+                        format!("unknown `value` configuration `{}`", arg),
+                    ));
+                }
+            })
+        } else {
+            Err(error(path, "invalid `value` configuration"))
+        }
+    }
+}
@@ -1,7 +1,10 @@
 mod arg;
+mod field;
+mod infer;
 mod soya;
 mod sub;
 
 pub use arg::ArgGenerator;
+pub use infer::InferGenerator;
 pub use soya::SoyaGenerator;
 pub use sub::SubGenerator;
@@ -1,19 +1,166 @@
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use syn::DeriveInput;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, Generics};
+
+use crate::config::value::Argument;
+use crate::config::Configs;
+use crate::error;
+
+#[derive(Debug)]
+struct Variant<'a> {
+    ident: &'a Ident,
+
+    name: String,
+
+    alias: Vec<String>,
+}
 
 pub struct ArgGenerator<'a> {
     ident: &'a Ident,
+
+    generics: &'a Generics,
+
+    variants: Vec<Variant<'a>>,
 }
 
 impl<'a> ArgGenerator<'a> {
     pub fn new(input: &'a DeriveInput) -> syn::Result<Self> {
         let ident = &input.ident;
+        let generics = &input.generics;
+        let data = match &input.data {
+            Data::Enum(data) => data,
+            _ => {
+                return Err(error(
+                    ident,
+                    "SoyaValue can only be derived for enums without fields",
+                ))
+            }
+        };
+        let mut variants = vec![];
+
+        for variant in data.variants.iter() {
+            if !matches!(variant.fields, Fields::Unit) {
+                return Err(error(
+                    variant,
+                    format!(
+                        "variant `{}` of SoyaValue can not have fields",
+                        variant.ident
+                    ),
+                ));
+            }
+            let configs = Configs::<Argument>::parse_attrs("value", &variant.attrs);
+            let name = match configs.find_value(Argument::Name) {
+                Some(value) => value.to_lit_str(value.span())?,
+                None => to_kebab_case(&variant.ident.to_string()),
+            };
+            let alias = configs
+                .find_values(Argument::Alias)
+                .into_iter()
+                .map(|v| v.to_lit_str(v.span()))
+                .collect::<syn::Result<Vec<String>>>()?;
 
-        Ok(Self { ident })
+            variants.push(Variant {
+                ident: &variant.ident,
+                name,
+                alias,
+            });
+        }
+        if variants.is_empty() {
+            return Err(error(ident, "SoyaValue needs at least one variant"));
+        }
+
+        Ok(Self {
+            ident,
+            generics,
+            variants,
+        })
     }
 
     pub fn generate_impl(&mut self) -> syn::Result<TokenStream> {
-        Ok(quote! {})
+        let ident = self.ident;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let names = self.variants.iter().map(|v| &v.name);
+        let expected = self
+            .variants
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let raws = self.variants.iter().map(|v| {
+            let variant = v.ident;
+            let name = &v.name;
+
+            quote! { Self::#variant => #name, }
+        });
+        let matches = self.variants.iter().map(|v| {
+            let variant = v.ident;
+            let name = &v.name;
+            let alias = &v.alias;
+
+            quote! {
+                if [#name #(, #alias)*].iter().any(|v| v.eq_ignore_ascii_case(&val)) {
+                    return Ok(Self::#variant);
+                }
+            }
+        });
+
+        Ok(quote! {
+            impl #impl_generics ::soya::infer::Infer for #ident #ty_generics #where_clause {
+                type Val = String;
+
+                fn infer_spec() -> ::soya::infer::OptSpec {
+                    ::soya::infer::OptSpec::new::<Self>()
+                        .with_possible_values(Some(vec![#(String::from(#names)),*]))
+                }
+
+                fn infer_map(val: Result<Self::Val, ::soya::err::Error>) -> Result<Self, ::soya::err::Error>
+                where
+                    Self: Sized,
+                {
+                    let val = val?;
+
+                    #(#matches)*
+                    Err(::soya::value::invalid_value(&val, format!("expected one of: {}", #expected)))
+                }
+
+                fn infer_mut(&mut self, val: Result<Self::Val, ::soya::err::Error>) -> Result<(), ::soya::err::Error> {
+                    *self = <Self as ::soya::infer::Infer>::infer_map(val)?;
+                    Ok(())
+                }
+            }
+
+            impl #impl_generics ::soya::to_args::ToRaw for #ident #ty_generics #where_clause {
+                fn to_raw(&self) -> Vec<::std::ffi::OsString> {
+                    let name = match self {
+                        #(#raws)*
+                    };
+
+                    vec![::std::ffi::OsString::from(name)]
+                }
+            }
+        })
+    }
+}
+
+/// Convert the variant name `FastMode` to `fast-mode`.
+fn to_kebab_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut ret = String::with_capacity(name.len() + 4);
+
+    for (i, ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next = chars.get(i + 1);
+
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next.map(|v| v.is_lowercase()).unwrap_or_default())
+            {
+                ret.push('-');
+            }
+        }
+        ret.extend(ch.to_lowercase());
     }
+    ret
 }
@@ -0,0 +1,437 @@
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{Attribute, Field, GenericArgument, Lit, PathArguments, Type};
+
+use crate::config::arg::Argument;
+use crate::config::sub::Argument as SubArgument;
+use crate::config::Configs;
+use crate::error;
+use crate::value::Value;
+
+#[derive(Debug)]
+enum Kind<'a> {
+    /// Option such as `--depth`, configured by `#[arg(...)]`.
+    Opt,
+
+    /// Positional argument at `index`, configured by `#[pos(...)]`.
+    Pos(usize),
+
+    /// Sub command `Option<T>` where `T` is a `ParserImpl`, configured by `#[sub(...)]`.
+    Sub(&'a Type),
+}
+
+pub struct FieldGenerator<'a> {
+    ident: &'a Ident,
+
+    ty: &'a Type,
+
+    kind: Kind<'a>,
+
+    name: String,
+
+    alias: Vec<String>,
+
+    help: String,
+
+    validate: Option<Value>,
+
+    conflicts: Vec<String>,
+
+    requires: Vec<String>,
+
+    required_if: Vec<String>,
+
+    groups: Vec<String>,
+
+    hide: bool,
+
+    complete: Option<Value>,
+
+    env: Option<String>,
+}
+
+impl<'a> FieldGenerator<'a> {
+    /// Create the generator of `field`, `pos_index` is the index of next positional argument.
+    pub fn new(field: &'a Field, pos_index: &mut usize) -> syn::Result<Self> {
+        let ident = field.ident.as_ref().ok_or_else(|| {
+            error(
+                field,
+                "Soya can only be derived for struct with named fields",
+            )
+        })?;
+        let ty = &field.ty;
+        let help = doc_string(&field.attrs);
+        let field_name = ident.to_string().trim_start_matches("r#").replace('_', "-");
+
+        if field.attrs.iter().any(|v| v.path().is_ident("sub")) {
+            let configs = Configs::<SubArgument>::parse_attrs("sub", &field.attrs);
+            let name = match configs.find_value(SubArgument::Name) {
+                Some(value) => value.to_lit_str(field.span())?,
+                None => field_name,
+            };
+            let alias = configs
+                .find_values(SubArgument::Alias)
+                .into_iter()
+                .map(|v| v.to_lit_str(field.span()))
+                .collect::<syn::Result<Vec<String>>>()?;
+            let inner = option_inner(ty)
+                .ok_or_else(|| error(ty, "the type of sub command must be `Option<T>`"))?;
+
+            return Ok(Self {
+                ident,
+                ty,
+                kind: Kind::Sub(inner),
+                name,
+                alias,
+                help,
+                validate: None,
+                conflicts: vec![],
+                requires: vec![],
+                required_if: vec![],
+                groups: vec![],
+                hide: false,
+                complete: None,
+                env: None,
+            });
+        }
+
+        let is_pos = field.attrs.iter().any(|v| v.path().is_ident("pos"));
+        let configs =
+            Configs::<Argument>::parse_attrs(if is_pos { "pos" } else { "arg" }, &field.attrs);
+        let alias = configs
+            .find_values(Argument::Alias)
+            .into_iter()
+            .map(|v| v.to_lit_str(field.span()))
+            .collect::<syn::Result<Vec<String>>>()?;
+        let validate = configs.find_value(Argument::Validate).cloned();
+        let conflicts = find_lit_strs(&configs, Argument::ConflictsWith, field.span())?;
+        let requires = find_lit_strs(&configs, Argument::Requires, field.span())?;
+        let required_if = find_lit_strs(&configs, Argument::RequiredIf, field.span())?;
+        let groups = find_lit_strs(&configs, Argument::Group, field.span())?;
+        let hide = configs.has_cfg(Argument::Hide);
+        let complete = configs.find_value(Argument::Complete).cloned();
+        let env = match configs.find_value(Argument::Env) {
+            Some(value) => Some(value.to_lit_str(field.span())?),
+            None => None,
+        };
+        let (name, kind) = if is_pos {
+            let index = match configs.find_value(Argument::Index) {
+                Some(Value::Literal(Lit::Int(lit))) => lit.base10_parse::<usize>()?,
+                Some(_) => return Err(error(field, "the index of `pos` must be an integer")),
+                None => *pos_index,
+            };
+
+            *pos_index += 1;
+            (field_name, Kind::Pos(index))
+        } else {
+            let name = if field_name.chars().count() == 1 {
+                format!("-{}", field_name)
+            } else {
+                format!("--{}", field_name)
+            };
+
+            (name, Kind::Opt)
+        };
+        let name = match configs.find_value(Argument::Name) {
+            Some(value) => value.to_lit_str(field.span())?,
+            None => name,
+        };
+
+        Ok(Self {
+            ident,
+            ty,
+            kind,
+            name,
+            alias,
+            help,
+            validate,
+            conflicts,
+            requires,
+            required_if,
+            groups,
+            hide,
+            complete,
+            env,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The callback return the candidates of option in completion.
+    pub fn complete(&self) -> Option<&Value> {
+        self.complete.as_ref()
+    }
+
+    /// The names of groups contain the option.
+    pub fn groups(&self) -> &[String] {
+        &self.groups
+    }
+
+    pub fn sub(&self) -> Option<(&str, &Type)> {
+        match &self.kind {
+            Kind::Sub(inner) => Some((&self.name, inner)),
+            _ => None,
+        }
+    }
+
+    /// The type implementing `Infer` of the option.
+    pub fn infer_type(&self) -> TokenStream {
+        let ty = self.ty;
+
+        match &self.kind {
+            Kind::Opt => quote! { #ty },
+            Kind::Pos(_) => quote! { ::soya::prelude::Pos<#ty> },
+            Kind::Sub(_) => quote! { ::soya::prelude::Cmd },
+        }
+    }
+
+    pub fn value_ident(&self, uid: usize) -> Ident {
+        format_ident!("value_{}", uid)
+    }
+
+    /// Create the option in `update`, the `parser` and `ctor_default` are in scope.
+    pub fn gen_option(&self) -> TokenStream {
+        let infer_type = self.infer_type();
+        let name = &self.name;
+        let alias = &self.alias;
+        let conflicts = &self.conflicts;
+        let requires = &self.requires;
+        let required_if = &self.required_if;
+        let hide = if self.hide {
+            quote! { opt.set_hidden(true); }
+        } else {
+            quote! {}
+        };
+        let env = self.env.iter();
+        let help = match &self.kind {
+            Kind::Sub(inner) if self.help.is_empty() => {
+                quote! { cfg.set_help(<#inner as ::soya::ParserImpl<'_>>::about()); }
+            }
+            _ if self.help.is_empty() => quote! {},
+            _ => {
+                let help = &self.help;
+
+                quote! { cfg.set_help(#help); }
+            }
+        };
+        let index = match &self.kind {
+            Kind::Pos(index) if last_ident_is(self.ty, "Vec") => {
+                quote! { cfg.set_index(::soya::prelude::Index::range(Some(#index), None)); }
+            }
+            Kind::Pos(index) => quote! { cfg.set_index(::soya::prelude::Index::Forward(#index)); },
+            _ => quote! {},
+        };
+
+        quote! {
+            {
+                let cfg = {
+                    let mut cfg = ::soya::prelude::SetCfg::<Self::Parser<'inv>>::default();
+
+                    cfg.set_name(#name);
+                    #(cfg.add_alias(#alias);)*
+                    #help
+                    #index
+                    <#infer_type as ::soya::infer::Infer>::infer_fill_info(&mut cfg)?;
+                    cfg
+                };
+                let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+                <#infer_type as ::soya::infer::Infer>::infer_fill_opt(&mut opt)?;
+                #(opt.add_conflict(#conflicts);)*
+                #(opt.add_require(#requires);)*
+                #(opt.add_required_if(#required_if);)*
+                #hide
+                #(opt.set_env(#env);)*
+                parser.insert(opt);
+            }
+        }
+    }
+
+    /// Declare the variable saving the value in `parse`.
+    pub fn gen_value_init(&self, uid: usize) -> TokenStream {
+        let value = self.value_ident(uid);
+        let infer_type = self.infer_type();
+
+        match &self.kind {
+            Kind::Sub(_) => quote! { let mut #value = None; },
+            _ => quote! { let mut #value = <#infer_type as ::soya::infer::Infer>::infer_new(); },
+        }
+    }
+
+    /// Register the handler of option `uid` in `parse`.
+    pub fn gen_handler(&self, uid: usize) -> TokenStream {
+        let value = self.value_ident(uid);
+        let infer_type = self.infer_type();
+        let uid = Literal::u64_unsuffixed(uid as u64);
+
+        match (&self.kind, &self.validate) {
+            (Kind::Sub(inner), _) => quote! {
+                parser
+                    .entry(#uid)?
+                    .on(|_set, ctx| {
+                        let index = ctx.idx()?;
+                        let mut args: Vec<_> = ctx.args().iter().map(|v| v.to_os_string()).collect();
+                        let name = args.remove(index);
+
+                        // keep the command path in the program name
+                        args[0].push(" ");
+                        args[0].push(name);
+                        #value = Some(<#inner as ::soya::ParserImpl<'_>>::parse(
+                            ::soya::prelude::Args::from(args),
+                        ));
+                        Ok(Some(()))
+                    })
+                    .then(::soya::prelude::NullStore);
+            },
+            (_, Some(validate)) => quote! {
+                parser
+                    .entry(#uid)?
+                    .on(failures.collect(::soya::fetch_or_update_handler!(#value, #infer_type, #validate)))
+                    .then(::soya::prelude::NullStore);
+            },
+            (_, None) => quote! {
+                parser
+                    .entry(#uid)?
+                    .on(failures.collect(::soya::fetch_or_update_handler!(#value, #infer_type)))
+                    .then(::soya::prelude::NullStore);
+            },
+        }
+    }
+
+    /// Take the failure of sub command out of the value before constructing.
+    pub fn gen_sub_failure(&self, uid: usize) -> TokenStream {
+        let value = self.value_ident(uid);
+        let error = format_ident!("error_{}", uid);
+
+        match &self.kind {
+            Kind::Sub(_) => quote! {
+                let (#value, #error) = match #value {
+                    Some(Err(e)) => (None, Some(e)),
+                    value => (value.and_then(Result::ok), None),
+                };
+            },
+            _ => quote! {},
+        }
+    }
+
+    /// Return the failure of sub command after the failures of command.
+    pub fn gen_sub_return(&self, uid: usize) -> TokenStream {
+        let error = format_ident!("error_{}", uid);
+
+        match &self.kind {
+            Kind::Sub(_) => quote! {
+                if let Some(error) = #error {
+                    return Err(error);
+                }
+            },
+            _ => quote! {},
+        }
+    }
+
+    /// The index of positional argument.
+    pub fn pos_index(&self) -> Option<usize> {
+        match &self.kind {
+            Kind::Pos(index) => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Append the arguments of field in `to_args`, the `args` is in scope.
+    pub fn gen_to_args(&self) -> TokenStream {
+        let ident = self.ident;
+        let name = &self.name;
+
+        match &self.kind {
+            Kind::Opt => quote! {
+                ::soya::to_args::ToRaw::push_opt(&self.#ident, #name, &mut args);
+            },
+            Kind::Pos(_) => quote! {
+                ::soya::to_args::ToRaw::push_pos(&self.#ident, &mut args);
+            },
+            Kind::Sub(_) => quote! {
+                if let Some(sub) = &self.#ident {
+                    args.push(::std::ffi::OsString::from(#name));
+                    args.extend(::soya::to_args::ToArgs::to_args(sub));
+                }
+            },
+        }
+    }
+
+    /// Initialize the field from the value.
+    pub fn gen_field(&self, uid: usize) -> TokenStream {
+        let ident = self.ident;
+        let value = self.value_ident(uid);
+        let msg = format!("Failed get value of field {}", ident);
+
+        match &self.kind {
+            Kind::Opt => quote! {
+                #ident: #value.ok_or_else(|| ::soya::err::err!(#msg))?
+            },
+            Kind::Pos(_) => quote! {
+                #ident: #value.ok_or_else(|| ::soya::err::err!(#msg)).map(|v| v.0)?
+            },
+            Kind::Sub(_) => quote! { #ident: #value },
+        }
+    }
+}
+
+/// Join the lines of document attributes `/// ...`.
+pub fn doc_string(attrs: &[Attribute]) -> String {
+    let mut lines = vec![];
+
+    for attr in attrs.iter().filter(|v| v.path().is_ident("doc")) {
+        if let syn::Meta::NameValue(meta) = &attr.meta {
+            if let syn::Expr::Lit(syn::ExprLit {
+                lit: Lit::Str(lit), ..
+            }) = &meta.value
+            {
+                lines.push(lit.value().trim().to_owned());
+            }
+        }
+    }
+    lines.join("\n").trim().to_owned()
+}
+
+fn find_lit_strs(
+    configs: &Configs<Argument>,
+    kind: Argument,
+    span: Span,
+) -> syn::Result<Vec<String>> {
+    let mut ret = vec![];
+
+    for value in configs.find_values(kind) {
+        ret.extend(value.to_lit_strs(span)?);
+    }
+    Ok(ret)
+}
+
+fn last_ident_is(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|v| v.ident == name)
+            .unwrap_or_default(),
+        _ => false,
+    }
+}
+
+/// Return `T` of `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    if let Type::Path(path) = ty {
+        let segment = path.path.segments.last()?;
+
+        if segment.ident == "Option" {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                    return Some(inner);
+                }
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,165 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Generics, Member, Type};
+
+use crate::config::infer::Argument;
+use crate::config::Configs;
+use crate::error;
+
+enum Kind<'a> {
+    /// Forward everything to the inner type of newtype.
+    NewType(Member, &'a Type),
+
+    FromStr,
+
+    TryFrom,
+}
+
+pub struct InferGenerator<'a> {
+    ident: &'a Ident,
+
+    generics: &'a Generics,
+
+    kind: Kind<'a>,
+}
+
+impl<'a> InferGenerator<'a> {
+    pub fn new(input: &'a DeriveInput) -> syn::Result<Self> {
+        let ident = &input.ident;
+        let generics = &input.generics;
+        let configs = Configs::<Argument>::parse_attrs("infer", &input.attrs);
+        let kind = if configs.has_cfg(Argument::FromStr) {
+            Kind::FromStr
+        } else if configs.has_cfg(Argument::TryFrom) {
+            Kind::TryFrom
+        } else {
+            let fields = match &input.data {
+                Data::Struct(data) => &data.fields,
+                _ => {
+                    return Err(error(
+                        ident,
+                        "Infer can only be derived for newtype, or add `#[infer(from_str)]` or `#[infer(try_from)]`",
+                    ))
+                }
+            };
+
+            match fields {
+                Fields::Named(fields) if fields.named.len() == 1 => {
+                    let field = fields.named.first().unwrap();
+
+                    Kind::NewType(Member::Named(field.ident.clone().unwrap()), &field.ty)
+                }
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    let field = fields.unnamed.first().unwrap();
+
+                    Kind::NewType(Member::Unnamed(0.into()), &field.ty)
+                }
+                _ => {
+                    return Err(error(
+                        ident,
+                        "Infer can only be derived for struct with exactly one field",
+                    ))
+                }
+            }
+        };
+
+        Ok(Self {
+            ident,
+            generics,
+            kind,
+        })
+    }
+
+    pub fn generate_impl(&mut self) -> syn::Result<TokenStream> {
+        let ident = self.ident;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let body = match &self.kind {
+            Kind::NewType(member, ty) => Self::gen_newtype(member, ty),
+            Kind::FromStr => Self::gen_convert(quote! {
+                ::soya::value::from_str(&val?)
+            }),
+            Kind::TryFrom => Self::gen_convert(quote! {
+                ::soya::value::try_from(val?)
+            }),
+        };
+
+        Ok(quote! {
+            impl #impl_generics ::soya::infer::Infer for #ident #ty_generics #where_clause {
+                #body
+            }
+        })
+    }
+
+    fn gen_convert(convert: TokenStream) -> TokenStream {
+        quote! {
+            type Val = String;
+
+            fn infer_spec() -> ::soya::infer::OptSpec {
+                let value_name = format!("<{}>", ::soya::infer::short_type_name::<Self>());
+
+                ::soya::infer::OptSpec::new::<Self>().with_value_name(Some(value_name))
+            }
+
+            fn infer_map(val: Result<Self::Val, ::soya::err::Error>) -> Result<Self, ::soya::err::Error>
+            where
+                Self: Sized,
+            {
+                #convert
+            }
+
+            fn infer_mut(&mut self, val: Result<Self::Val, ::soya::err::Error>) -> Result<(), ::soya::err::Error> {
+                *self = <Self as ::soya::infer::Infer>::infer_map(val)?;
+                Ok(())
+            }
+        }
+    }
+
+    fn gen_newtype(member: &Member, ty: &Type) -> TokenStream {
+        let inner = quote! { <#ty as ::soya::infer::Infer> };
+
+        quote! {
+            type Val = #inner::Val;
+
+            fn infer_spec() -> ::soya::infer::OptSpec {
+                #inner::infer_spec()
+            }
+
+            fn infer_new() -> Option<Self>
+            where
+                Self: Sized,
+            {
+                #inner::infer_new().map(|v| Self { #member: v })
+            }
+
+            fn infer_map(val: Result<Self::Val, ::soya::err::Error>) -> Result<Self, ::soya::err::Error>
+            where
+                Self: Sized,
+            {
+                #inner::infer_map(val).map(|v| Self { #member: v })
+            }
+
+            fn infer_mut(&mut self, val: Result<Self::Val, ::soya::err::Error>) -> Result<(), ::soya::err::Error> {
+                #inner::infer_mut(&mut self.#member, val)
+            }
+
+            fn infer_map_with(ctx: &::soya::infer::InferCtx, val: Result<Self::Val, ::soya::err::Error>) -> Result<Self, ::soya::err::Error>
+            where
+                Self: Sized,
+            {
+                #inner::infer_map_with(ctx, val).map(|v| Self { #member: v })
+            }
+
+            fn infer_mut_with(&mut self, ctx: &::soya::infer::InferCtx, val: Result<Self::Val, ::soya::err::Error>) -> Result<(), ::soya::err::Error> {
+                #inner::infer_mut_with(&mut self.#member, ctx, val)
+            }
+
+            fn infer_tweak_info<C>(cfg: &mut C) -> Result<(), ::soya::err::Error>
+            where
+                Self: Sized,
+                C: ::soya::aopt::prelude::ConfigValue + Default,
+            {
+                #inner::infer_tweak_info(cfg)
+            }
+        }
+    }
+}
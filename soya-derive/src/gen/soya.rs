@@ -1,18 +1,24 @@
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::quote;
-use syn::{DeriveInput, Generics};
+use syn::{parse_quote, Data, DeriveInput, Expr, ExprLit, Fields, Generics, Lit};
 
+use super::field::doc_string;
+use super::field::FieldGenerator;
 use crate::config::soya::Argument;
 use crate::config::Configs;
+use crate::error;
+use crate::value::Value;
 
 pub struct SoyaGenerator<'a> {
-    name: TokenStream,
-
     ident: &'a Ident,
 
     generics: &'a Generics,
 
     configs: Configs<Argument>,
+
+    about: String,
+
+    fields: Vec<FieldGenerator<'a>>,
 }
 
 impl<'a> SoyaGenerator<'a> {
@@ -20,17 +26,464 @@ impl<'a> SoyaGenerator<'a> {
         let ident = &input.ident;
         let generics = &input.generics;
         let configs = Configs::<Argument>::parse_attrs("soya", &input.attrs);
-        let name = quote! {};
+        let about = doc_string(&input.attrs);
+        let fields = match &input.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(fields) => &fields.named,
+                Fields::Unit => {
+                    return Ok(Self {
+                        ident,
+                        generics,
+                        configs,
+                        about,
+                        fields: vec![],
+                    })
+                }
+                Fields::Unnamed(_) => {
+                    return Err(error(
+                        ident,
+                        "Soya can only be derived for struct with named fields",
+                    ))
+                }
+            },
+            _ => return Err(error(ident, "Soya can only be derived for struct")),
+        };
+        let mut pos_index = 1;
+        let fields = fields
+            .iter()
+            .map(|field| FieldGenerator::new(field, &mut pos_index))
+            .collect::<syn::Result<Vec<_>>>()?;
 
         Ok(Self {
             ident,
             generics,
             configs,
-            name,
+            about,
+            fields,
+        })
+    }
+
+    fn gen_policy(&self) -> syn::Result<TokenStream> {
+        let policy = match self.configs.find_value(Argument::Policy) {
+            Some(value) => value.to_lit_str(self.ident.span())?,
+            None => "fwd".to_owned(),
+        };
+
+        Ok(match policy.as_str() {
+            "fwd" => quote! { ::soya::prelude::FwdPolicy<'a> },
+            "pre" => quote! { ::soya::prelude::PrePolicy<'a> },
+            "delay" => quote! { ::soya::prelude::DelayPolicy<'a> },
+            "seq" => quote! { ::soya::prelude::SeqPolicy<'a> },
+            _ => {
+                return Err(error(
+                    self.ident,
+                    format!(
+                        "unknown policy `{}`, expected one of: fwd, pre, delay, seq",
+                        policy
+                    ),
+                ))
+            }
+        })
+    }
+
+    /// The groups declared by `#[soya(group("name", required, multiple))]` and the groups
+    /// of options, the group of options only is at most one of them.
+    fn gen_groups(&self) -> syn::Result<TokenStream> {
+        let mut groups: Vec<(String, bool, bool)> = vec![];
+
+        for value in self.configs.find_values(Argument::Group) {
+            match value {
+                Value::Call(args) if !args.is_empty() => {
+                    let name = match &args[0] {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Str(lit), ..
+                        }) => lit.value(),
+                        arg => return Err(error(arg, "expected the name of group")),
+                    };
+                    let mut group = (name, false, false);
+
+                    for arg in args.iter().skip(1) {
+                        match arg {
+                            Expr::Path(path) if path.path.is_ident("required") => group.1 = true,
+                            Expr::Path(path) if path.path.is_ident("multiple") => group.2 = true,
+                            arg => {
+                                return Err(error(
+                                    arg,
+                                    "expected `required` or `multiple` of group",
+                                ))
+                            }
+                        }
+                    }
+                    groups.push(group);
+                }
+                value => groups.push((value.to_lit_str(self.ident.span())?, false, false)),
+            }
+        }
+        for field in self.fields.iter() {
+            for name in field.groups() {
+                if !groups.iter().any(|v| &v.0 == name) {
+                    groups.push((name.clone(), false, false));
+                }
+            }
+        }
+
+        let groups = groups
+            .iter()
+            .map(|(name, required, multiple)| {
+                let opts = self
+                    .fields
+                    .iter()
+                    .filter(|v| v.groups().contains(name))
+                    .map(|v| v.name())
+                    .collect::<Vec<_>>();
+
+                if opts.is_empty() {
+                    return Err(error(
+                        self.ident,
+                        format!("group `{}` does not have any option", name),
+                    ));
+                }
+                Ok(quote! {
+                    ::soya::prelude::Group::new(#name)
+                        .with_opts([#(#opts),*])
+                        .with_required(#required)
+                        .with_multiple(#multiple)
+                })
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        Ok(quote! {
+            let groups: Vec<::soya::prelude::Group> = vec![#(#groups),*];
+        })
+    }
+
+    fn gen_validate(&self) -> TokenStream {
+        match self.configs.find_value(Argument::Validate) {
+            Some(validate) => quote! {
+                fn validate(&self) -> Result<(), Self::Error> {
+                    #validate(self)
+                }
+            },
+            None => quote! {},
+        }
+    }
+
+    fn gen_describe(&self) -> TokenStream {
+        let subs = self
+            .fields
+            .iter()
+            .filter_map(|v| v.sub())
+            .map(|(name, ty)| {
+                quote! { .with_sub(<#ty as ::soya::ParserImpl<'_>>::describe(#name)?) }
+            });
+
+        if self.fields.iter().any(|v| v.sub().is_some()) {
+            quote! {
+                fn describe(name: &str) -> Result<::soya::prelude::CmdDesc, Self::Error> {
+                    Ok(<Self as ::soya::ParserImpl<'_>>::describe_opts(name)? #(#subs)*)
+                }
+            }
+        } else {
+            quote! {}
+        }
+    }
+
+    /// Set the callbacks of options and use the completion of sub commands.
+    fn gen_completion(&self) -> TokenStream {
+        let completes = self.fields.iter().filter_map(|v| {
+            let name = v.name();
+
+            v.complete().map(|complete| {
+                quote! { let completion = completion.with_complete(#name, #complete)?; }
+            })
+        });
+        let subs = self
+            .fields
+            .iter()
+            .filter_map(|v| v.sub())
+            .map(|(name, ty)| {
+                quote! {
+                    let completion = completion.with_sub(#name, |_| {
+                        <#ty as ::soya::ParserImpl<'_>>::completion(#name)
+                    })?;
+                }
+            });
+
+        if self
+            .fields
+            .iter()
+            .any(|v| v.complete().is_some() || v.sub().is_some())
+        {
+            quote! {
+                fn completion(name: &str) -> Result<::soya::prelude::Completion, Self::Error> {
+                    let completion = ::soya::prelude::Completion::new(
+                        &<Self as ::soya::ParserImpl<'_>>::describe(name)?,
+                    );
+
+                    #(#completes)*
+                    #(#subs)*
+                    Ok(completion)
+                }
+            }
+        } else {
+            quote! {}
+        }
+    }
+
+    /// A hidden [`Stop`](soya::prelude::Stop) option accepts the `--` written before the
+    /// positional arguments start with `-`, the sub command parses the `--` itself.
+    fn has_stop(&self) -> bool {
+        self.fields.iter().any(|v| v.pos_index().is_some())
+            && self.fields.iter().all(|v| v.sub().is_none())
+    }
+
+    fn gen_parse(&self) -> syn::Result<TokenStream> {
+        let groups = self.gen_groups()?;
+        let help_uid = Literal::u64_unsuffixed(self.fields.len() as u64);
+        let inits = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(uid, v)| v.gen_value_init(uid));
+        let handlers = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(uid, v)| v.gen_handler(uid));
+        let sub_failures = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(uid, v)| v.gen_sub_failure(uid));
+        let sub_returns = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(uid, v)| v.gen_sub_return(uid));
+        let fields = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(uid, v)| v.gen_field(uid));
+        let (stop_init, stop) = if self.has_stop() {
+            let stop_uid = Literal::u64_unsuffixed(self.fields.len() as u64 + 1);
+
+            (
+                quote! {
+                    let mut value_stop = <Option<::soya::prelude::Stop> as ::soya::infer::Infer>::infer_new();
+                },
+                quote! {
+                    parser
+                    .entry(#stop_uid)?
+                    .on(failures.collect(::soya::fetch_or_update_handler!(value_stop, Option<::soya::prelude::Stop>)))
+                        .then(::soya::prelude::NullStore);
+                },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+
+        Ok(quote! {
+            fn parse(args: ::soya::prelude::Args) -> Result<Self, Self::Error>
+            where
+                Self: 'inv + Sized,
+            {
+                use ::soya::prelude::PolicyParser as _;
+
+                #(#inits)*
+                let mut value_help = <bool as ::soya::infer::Infer>::infer_new();
+                #stop_init
+                #groups
+                let failures = ::soya::prelude::Failures::new(<Self as ::soya::ParserImpl<'_>>::collect_failures());
+                let mut parser = <Self as ::soya::ParserImpl<'_>>::into_parser()?;
+                let mut policy = <Self as ::soya::ParserImpl<'_>>::into_policy()?;
+
+                #(#handlers)*
+                parser
+                    .entry(#help_uid)?
+                    .on(failures.collect(::soya::fetch_or_update_handler!(value_help, bool)))
+                    .then(::soya::prelude::NullStore);
+                #stop
+
+                let args = failures
+                    .ok(::soya::prelude::group_values(&parser, args.clone()))
+                    .unwrap_or(args);
+                let args = ::soya::prelude::env_values(&parser, args);
+                let cmd = args
+                    .first()
+                    .map(|v| v.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let mut ret = parser.parse_policy(args.clone(), &mut policy)?;
+                let help = ::soya::prelude::display_help(&parser, &cmd, &groups);
+                let relations = ::soya::prelude::relation_failures(&parser, &args, &groups)?;
+
+                drop(parser);
+                drop(policy);
+
+                if value_help == Some(true) {
+                    println!("{}", help);
+                }
+                if let Some(error) = ret.take_failure() {
+                    failures.push(error);
+                }
+                for failure in relations {
+                    failures.push(failure);
+                }
+                // the failure of sub command is reported with its own command path,
+                // after the failures of this command
+                #(#sub_failures)*
+                // the value is incomplete if there are failures already
+                let value = if failures.is_empty() {
+                    failures.ok((|| -> Result<Self, Self::Error> {
+                        let value = Self {
+                            #(#fields,)*
+                        };
+
+                        <Self as ::soya::ParserImpl<'_>>::validate(&value)?;
+                        Ok(value)
+                    })())
+                } else {
+                    None
+                };
+
+                failures
+                    .into_result()
+                    .map_err(|e| ::soya::failure::usage_failure(&cmd, e))?;
+                #(#sub_returns)*
+                value.ok_or_else(|| ::soya::err::err!("Failed get value of command {}", cmd))
+            }
         })
     }
 
     pub fn generate_impl(&mut self) -> syn::Result<TokenStream> {
-        Ok(quote! {})
+        let ident = self.ident;
+        let mut generics = self.generics.clone();
+
+        generics.params.insert(0, parse_quote!('inv));
+
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let (_, ty_generics, _) = self.generics.split_for_impl();
+        let policy = self.gen_policy()?;
+        let about = &self.about;
+        let options = self.fields.iter().map(|v| v.gen_option());
+        let validate = self.gen_validate();
+        let describe = self.gen_describe();
+        let completion = self.gen_completion();
+        let parse = self.gen_parse()?;
+        let stop = if self.has_stop() {
+            quote! {
+                {
+                    let cfg = {
+                        let mut cfg = ::soya::prelude::SetCfg::<Self::Parser<'inv>>::default();
+
+                        <Option<::soya::prelude::Stop> as ::soya::infer::Infer>::infer_fill_info(&mut cfg)?;
+                        cfg
+                    };
+                    let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+                    <Option<::soya::prelude::Stop> as ::soya::infer::Infer>::infer_fill_opt(&mut opt)?;
+                    opt.set_hidden(true);
+                    parser.insert(opt);
+                }
+            }
+        } else {
+            quote! {}
+        };
+
+        Ok(quote! {
+            impl #impl_generics ::soya::ParserImpl<'inv> for #ident #ty_generics #where_clause {
+                type Error = ::soya::err::Error;
+
+                type Parser<'a> = ::soya::prelude::OptSet<'a>
+                where
+                    Self: 'a;
+
+                type Policy<'a> = #policy
+                where
+                    Self: 'a;
+
+                fn update(parser: &mut Self::Parser<'inv>) -> Result<(), Self::Error> {
+                    use ::soya::aopt::opt::ConfigValue as _;
+                    use ::soya::aopt::set::Ctor as _;
+                    use ::soya::aopt::set::Set as _;
+                    use ::soya::aopt::set::SetExt as _;
+
+                    let ctor_default = ::soya::prelude::ctor_default_name();
+
+                    #(#options)*
+                    {
+                        let cfg = {
+                            let mut cfg = ::soya::prelude::SetCfg::<Self::Parser<'inv>>::default();
+
+                            cfg.set_name("-h");
+                            cfg.add_alias("--help");
+                            <bool as ::soya::infer::Infer>::infer_fill_info(&mut cfg)?;
+                            cfg
+                        };
+                        let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+                        <bool as ::soya::infer::Infer>::infer_fill_opt(&mut opt)?;
+                        parser.insert(opt);
+                    }
+                    #stop
+                    Ok(())
+                }
+
+                fn apply_settings(_policy: &mut Self::Policy<'inv>) -> Result<(), Self::Error> {
+                    Ok(())
+                }
+
+                fn about() -> &'static str {
+                    #about
+                }
+
+                #validate
+
+                #describe
+
+                #completion
+
+                #parse
+            }
+        })
+    }
+
+    /// The options first, then the positional arguments in the order of index, and the
+    /// matched sub command last, same as the order of [`ToArgs`](soya::to_args::ToArgs).
+    pub fn generate_to_args(&mut self) -> syn::Result<TokenStream> {
+        let ident = self.ident;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let mut poses = self
+            .fields
+            .iter()
+            .filter(|v| v.pos_index().is_some())
+            .collect::<Vec<_>>();
+
+        poses.sort_by_key(|v| v.pos_index());
+
+        let opts = self
+            .fields
+            .iter()
+            .filter(|v| v.pos_index().is_none() && v.sub().is_none())
+            .map(|v| v.gen_to_args());
+        let poses = poses.iter().map(|v| v.gen_to_args());
+        let subs = self
+            .fields
+            .iter()
+            .filter(|v| v.sub().is_some())
+            .map(|v| v.gen_to_args());
+
+        Ok(quote! {
+            impl #impl_generics ::soya::to_args::ToArgs for #ident #ty_generics #where_clause {
+                fn to_args(&self) -> Vec<::std::ffi::OsString> {
+                    let mut args = vec![];
+
+                    #(#opts)*
+                    #(#poses)*
+                    #(#subs)*
+                    args
+                }
+            }
+        })
     }
 }
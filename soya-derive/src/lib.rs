@@ -2,6 +2,8 @@ mod config;
 mod gen;
 mod value;
 
+use gen::ArgGenerator;
+use gen::InferGenerator;
 use gen::SoyaGenerator;
 use quote::quote;
 use syn::parse_macro_input;
@@ -30,3 +32,57 @@ pub fn parser(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     }
     .into()
 }
+
+#[proc_macro_derive(ToArgs, attributes(soya, arg, pos, cmd, sub))]
+pub fn to_args(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+
+    let generator = |input: DeriveInput| -> syn::Result<proc_macro2::TokenStream> {
+        let mut cg = SoyaGenerator::new(&input)?;
+
+        cg.generate_to_args()
+    };
+
+    let ts = generator(input).unwrap_or_else(syn::Error::into_compile_error);
+
+    quote! {
+        #ts
+    }
+    .into()
+}
+
+#[proc_macro_derive(SoyaValue, attributes(value))]
+pub fn value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+
+    let generator = |input: DeriveInput| -> syn::Result<proc_macro2::TokenStream> {
+        let mut cg = ArgGenerator::new(&input)?;
+
+        cg.generate_impl()
+    };
+
+    let ts = generator(input).unwrap_or_else(syn::Error::into_compile_error);
+
+    quote! {
+        #ts
+    }
+    .into()
+}
+
+#[proc_macro_derive(Infer, attributes(infer))]
+pub fn infer(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+
+    let generator = |input: DeriveInput| -> syn::Result<proc_macro2::TokenStream> {
+        let mut cg = InferGenerator::new(&input)?;
+
+        cg.generate_impl()
+    };
+
+    let ts = generator(input).unwrap_or_else(syn::Error::into_compile_error);
+
+    quote! {
+        #ts
+    }
+    .into()
+}
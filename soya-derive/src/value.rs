@@ -7,6 +7,7 @@ use syn::parenthesized;
 use syn::parse::Parse;
 use syn::token::Paren;
 use syn::Expr;
+use syn::ExprLit;
 use syn::Lit;
 use syn::LitInt;
 use syn::Token;
@@ -38,6 +39,30 @@ impl Value {
             "You must specify the context variable name for raw method call".to_owned(),
         ))
     }
+
+    pub fn to_lit_str(&self, span: Span) -> syn::Result<String> {
+        if let Value::Literal(Lit::Str(lit)) = self {
+            Ok(lit.value())
+        } else {
+            Err(error(span, "expected a string literal".to_owned()))
+        }
+    }
+
+    /// Return the string literals of `name = "..."` or `name("...", "...")`.
+    pub fn to_lit_strs(&self, span: Span) -> syn::Result<Vec<String>> {
+        match self {
+            Value::Call(args) => args
+                .iter()
+                .map(|arg| match arg {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(lit), ..
+                    }) => Ok(lit.value()),
+                    _ => Err(error(arg, "expected a string literal".to_owned())),
+                })
+                .collect(),
+            _ => self.to_lit_str(span).map(|v| vec![v]),
+        }
+    }
 }
 
 impl ToTokens for Value {
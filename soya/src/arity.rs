@@ -0,0 +1,179 @@
+use std::ffi::OsString;
+
+use aopt::args::Args;
+use aopt::set::Ctor;
+use aopt::set::Set;
+
+use crate::err::fail;
+use crate::opt::Opt;
+use crate::token::find_in;
+use crate::token::Takes;
+use crate::token::Token;
+use crate::token::Tokens;
+use crate::Error;
+
+/// The separator used to join the values of option which consume multiple arguments.
+pub const VALUE_SEPARATOR: char = '\u{1f}';
+
+/// Split the values joined by [`group_values`], return a failure if the count is not in `arity`.
+pub fn split_values(val: &str, min: usize, max: usize) -> Result<Vec<&str>, Error> {
+    let values: Vec<_> = val.split(VALUE_SEPARATOR).collect();
+
+    if values.len() < min || values.len() > max {
+        Err(fail!(
+            "expected {} values, but found {}",
+            display_arity(min, max),
+            values.len()
+        ))
+    } else {
+        Ok(values)
+    }
+}
+
+fn display_arity(min: usize, max: usize) -> String {
+    if min == max {
+        format!("{}", min)
+    } else if max == usize::MAX {
+        format!("at least {}", min)
+    } else {
+        format!("{} to {}", min, max)
+    }
+}
+
+pub(crate) fn like_option(arg: &str) -> bool {
+    arg.len() > 1 && arg.starts_with('-') && arg.parse::<f64>().is_err()
+}
+
+/// Join the arguments following the option which consume multiple values, such as `--point 1 2`,
+/// into a single argument `--point=1\u{1f}2`, so the parser can process it as normal option.
+/// The argument already joined is kept as it is.
+///
+/// Return a failure if there are not enough values after the option.
+pub fn group_values<S>(set: &S, args: Args) -> Result<Args, Error>
+where
+    S: Set,
+    S::Ctor: Ctor<Opt = Opt>,
+{
+    if set.iter().all(|opt| opt.arity().is_none()) {
+        return Ok(args);
+    }
+
+    let find = find_in(set);
+    let mut ret: Vec<OsString> = Vec::with_capacity(args.len());
+    let mut tokens = Tokens::new(args.get(1..).unwrap_or_default());
+
+    // skip the program name
+    ret.extend(args.first().cloned());
+    while let Some((token, raw)) = tokens.next_token(&find) {
+        match token {
+            Token::Opt { opt, name, values } if opt.arity().is_some() => {
+                let (min, max) = Takes::new(opt.styles(), opt.arity()).value_range();
+
+                if values.len() < min {
+                    return Err(fail!(
+                        "option `{}` requires {} values, but found {}",
+                        name,
+                        display_arity(min, max),
+                        values.len()
+                    ));
+                }
+                let mut arg = OsString::from(format!("{}=", name));
+
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        arg.push(VALUE_SEPARATOR.to_string());
+                    }
+                    arg.push(value);
+                }
+                ret.push(arg);
+            }
+            _ => ret.extend(raw.iter().cloned()),
+        }
+    }
+
+    Ok(Args::from(ret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::OptSet;
+    use crate::test_util::insert_opt;
+    use crate::value::Multi;
+
+    fn grouped(set: &OptSet, args: &[&str]) -> Result<Vec<String>, Error> {
+        let args: Vec<OsString> = group_values(set, Args::from(args.to_vec()))?.into();
+
+        Ok(args
+            .iter()
+            .map(|v| v.to_string_lossy().replace(VALUE_SEPARATOR, "|"))
+            .collect())
+    }
+
+    fn option_set() -> Result<OptSet<'static>, Error> {
+        let mut set = OptSet::default();
+
+        insert_opt::<bool>(&mut set, "--debug", |_| {})?;
+        insert_opt::<Option<(i64, i64)>>(&mut set, "--point", |_| {})?;
+        insert_opt::<Option<Multi<String, 1, 3>>>(&mut set, "--tag", |_| {})?;
+        Ok(set)
+    }
+
+    #[test]
+    fn group_fixed_values() -> Result<(), Error> {
+        let set = option_set()?;
+
+        assert_eq!(
+            grouped(&set, &["tool", "--point", "1", "-2", "x"])?,
+            ["tool", "--point=1|-2", "x"]
+        );
+        assert_eq!(
+            grouped(&set, &["tool", "--point=1", "2", "--debug"])?,
+            ["tool", "--point=1|2", "--debug"]
+        );
+        let error = grouped(&set, &["tool", "--point", "1", "--debug"]).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("option `--point` requires 2 values, but found 1"),
+            "{}",
+            error
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn group_variable_values() -> Result<(), Error> {
+        let set = option_set()?;
+
+        assert_eq!(
+            grouped(&set, &["tool", "--tag", "a", "b", "--debug", "c"])?,
+            ["tool", "--tag=a|b", "--debug", "c"]
+        );
+        assert_eq!(
+            grouped(&set, &["tool", "--tag", "a", "b", "c", "d"])?,
+            ["tool", "--tag=a|b|c", "d"]
+        );
+        // the arguments after `--` and the values already joined are kept
+        assert_eq!(
+            grouped(&set, &["tool", "--tag=a\u{1f}b", "c", "--", "--tag", "d"])?,
+            ["tool", "--tag=a|b", "c", "--", "--tag", "d"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn split_values_of_arity() {
+        assert_eq!(split_values("1\u{1f}2", 2, 2).unwrap(), ["1", "2"]);
+        assert_eq!(split_values("a", 1, 3).unwrap(), ["a"]);
+        assert!(split_values("1\u{1f}2\u{1f}3", 1, 2)
+            .unwrap_err()
+            .to_string()
+            .contains("expected 1 to 2 values, but found 3"));
+        assert!(split_values("1", 2, usize::MAX)
+            .unwrap_err()
+            .to_string()
+            .contains("expected at least 2 values, but found 1"));
+    }
+}
@@ -0,0 +1,618 @@
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::rc::Rc;
+
+use aopt::ctx::Ctx;
+use aopt::opt::Cmd;
+use aopt::opt::ConfigValue;
+use aopt::opt::Index;
+use aopt::opt::Pos;
+use aopt::parser::PolicyParser;
+use aopt::set::Ctor;
+use aopt::set::Set;
+use aopt::set::SetExt;
+use aopt::value::RawValParser;
+
+use crate::arity::group_values;
+use crate::describe::CmdDesc;
+use crate::env::env_values;
+use crate::failure::usage_failure;
+use crate::failure::Failures;
+use crate::infer::Infer;
+use crate::infer::InferCtx;
+use crate::prelude::Args;
+use crate::prelude::FwdPolicy;
+use crate::prelude::NullStore;
+use crate::prelude::OptSet;
+use crate::prelude::PrePolicy;
+use crate::prelude::SetCfg;
+use crate::relation::relation_failures;
+use crate::Error;
+
+#[derive(Debug, Clone, Default)]
+struct OptInfo {
+    name: String,
+    aliases: Vec<String>,
+    index: Option<Index>,
+    force: Option<bool>,
+    help: String,
+    env: Option<String>,
+    default: Option<String>,
+    conflicts: Vec<String>,
+    requires: Vec<String>,
+    required_if: Vec<String>,
+}
+
+impl OptInfo {
+    fn new(name: String, index: Option<Index>) -> Self {
+        Self {
+            name,
+            index,
+            ..Default::default()
+        }
+    }
+
+    fn fill<C: ConfigValue>(&self, cfg: &mut C) {
+        cfg.set_name(&self.name);
+        for alias in self.aliases.iter() {
+            cfg.add_alias(alias);
+        }
+        if let Some(index) = &self.index {
+            cfg.set_index(index.clone());
+        }
+        if let Some(force) = self.force {
+            cfg.set_force(force);
+        }
+        if !self.help.is_empty() {
+            cfg.set_help(&self.help);
+        }
+    }
+}
+
+type DefaultFn<T> = Box<dyn Fn() -> T>;
+
+/// The typed handle of option returned by [`Command`], the option is configured through it
+/// when building and the value is available after the command parsed.
+pub struct Handle<T> {
+    value: Rc<RefCell<Option<T>>>,
+    info: Rc<RefCell<OptInfo>>,
+    default: Rc<RefCell<Option<DefaultFn<T>>>>,
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Handle")
+            .field("value", &self.value)
+            .field("info", &self.info)
+            .finish()
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            info: self.info.clone(),
+            default: self.default.clone(),
+        }
+    }
+}
+
+impl<T> Handle<T> {
+    fn new(info: OptInfo) -> Self {
+        Self {
+            value: Rc::default(),
+            info: Rc::new(RefCell::new(info)),
+            default: Rc::default(),
+        }
+    }
+
+    pub fn alias(self, alias: impl Into<String>) -> Self {
+        self.info.borrow_mut().aliases.push(alias.into());
+        self
+    }
+
+    pub fn help(self, help: impl Into<String>) -> Self {
+        self.info.borrow_mut().help = help.into();
+        self
+    }
+
+    /// Override the force inferred from the type.
+    pub fn force(self, force: bool) -> Self {
+        self.info.borrow_mut().force = Some(force);
+        self
+    }
+
+    /// Read the value from the environment variable `env` if the option is not present.
+    pub fn env(self, env: impl Into<String>) -> Self {
+        self.info.borrow_mut().env = Some(env.into());
+        self
+    }
+
+    /// The option can not be used together with option `name`.
+    pub fn conflicts_with(self, name: impl Into<String>) -> Self {
+        self.info.borrow_mut().conflicts.push(name.into());
+        self
+    }
+
+    /// The option `name` must be present when this option is present.
+    pub fn requires(self, name: impl Into<String>) -> Self {
+        self.info.borrow_mut().requires.push(name.into());
+        self
+    }
+
+    /// The option is required when option `name` is present, or has the value such as
+    /// `--format=json`.
+    pub fn required_if(self, name: impl Into<String>) -> Self {
+        self.info.borrow_mut().required_if.push(name.into());
+        self
+    }
+
+    /// The value used if the option is not present, it is displayed as `[default: ..]`
+    /// in the help message, such as `ByteSize::new(4096)` displayed as `4KiB`.
+    ///
+    /// The option with default value is not force required.
+    pub fn default_value<V>(self, value: V) -> Self
+    where
+        V: Clone + Display + Into<T> + 'static,
+    {
+        self.info.borrow_mut().force = Some(false);
+        self.info.borrow_mut().default = Some(value.to_string());
+        *self.default.borrow_mut() = Some(Box::new(move || value.clone().into()));
+        self
+    }
+
+    pub fn name(&self) -> String {
+        self.info.borrow().name.clone()
+    }
+
+    /// Return `true` if the value is set, such as the option is present.
+    pub fn is_some(&self) -> bool {
+        self.value.borrow().is_some()
+    }
+
+    /// Take the value out of handle.
+    pub fn take(&self) -> Option<T> {
+        self.value.borrow_mut().take()
+    }
+
+    /// Get a copy of the value.
+    pub fn get(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.value.borrow().clone()
+    }
+}
+
+trait Slot {
+    fn reset(&self);
+
+    /// Set the default value if the option is not present.
+    fn fill_default(&self);
+
+    fn register<'a>(&self, parser: &mut OptSet<'a>, failures: &'a Failures) -> Result<(), Error>;
+
+    fn info(&self) -> OptInfo;
+}
+
+/// The slot of value `T` which is parsed as `P`, such as `Pos<T>` for positional argument.
+struct TypedSlot<T, P> {
+    handle: Handle<T>,
+    matched: Rc<Cell<bool>>,
+    wrap: fn(T) -> P,
+    unwrap: fn(P) -> T,
+}
+
+impl<T, P> Slot for TypedSlot<T, P>
+where
+    T: 'static,
+    P: Infer,
+    P::Val: RawValParser,
+{
+    fn reset(&self) {
+        *self.handle.value.borrow_mut() = P::infer_new().map(self.unwrap);
+        self.matched.set(false);
+    }
+
+    fn fill_default(&self) {
+        if let (false, Some(default)) = (self.matched.get(), &*self.handle.default.borrow()) {
+            *self.handle.value.borrow_mut() = Some(default());
+        }
+    }
+
+    fn register<'a>(&self, parser: &mut OptSet<'a>, failures: &'a Failures) -> Result<(), Error> {
+        let value = self.handle.value.clone();
+        let matched = self.matched.clone();
+        let (wrap, unwrap) = (self.wrap, self.unwrap);
+
+        add_opt::<P>(
+            parser,
+            failures,
+            &self.handle.info.borrow(),
+            move |infer_ctx, val| {
+                let mut value = value.borrow_mut();
+
+                matched.set(true);
+                match value.take() {
+                    Some(exist) => {
+                        let mut exist = wrap(exist);
+                        let ret = exist.infer_mut_with(infer_ctx, val);
+
+                        *value = Some(unwrap(exist));
+                        ret
+                    }
+                    None => {
+                        *value = Some(unwrap(P::infer_map_with(infer_ctx, val)?));
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    fn info(&self) -> OptInfo {
+        self.handle.info.borrow().clone()
+    }
+}
+
+/// Create the option `T` configured by `info`, the `handler` is called with the value of
+/// option each time it is matched.
+fn add_opt<'a, T>(
+    parser: &mut OptSet<'a>,
+    failures: &'a Failures,
+    info: &OptInfo,
+    mut handler: impl FnMut(&InferCtx, Result<T::Val, Error>) -> Result<(), Error> + 'a,
+) -> Result<(), Error>
+where
+    T: Infer,
+    T::Val: RawValParser,
+{
+    let mut cfg = SetCfg::<OptSet<'a>>::default();
+
+    info.fill(&mut cfg);
+    T::infer_fill_info(&mut cfg)?;
+
+    let mut opt = parser
+        .ctor_mut(&aopt::set::ctor_default_name())?
+        .new_with(cfg)?;
+
+    T::infer_fill_opt(&mut opt)?;
+    if let Some(env) = &info.env {
+        opt.set_env(env);
+    }
+    if let Some(default) = &info.default {
+        opt.set_default_value(default);
+    }
+    for name in info.conflicts.iter() {
+        opt.add_conflict(name);
+    }
+    for name in info.requires.iter() {
+        opt.add_require(name);
+    }
+    for name in info.required_if.iter() {
+        opt.add_required_if(name);
+    }
+
+    let uid = parser.insert(opt);
+
+    parser
+        .entry(uid)?
+        .on(
+            failures.collect(move |set: &mut OptSet<'a>, ctx: &mut Ctx| {
+                let infer_ctx = InferCtx::from_ctx::<T, _>(set, ctx)?;
+                let val = ctx.value::<T::Val>().map_err(|e| infer_ctx.wrap(e));
+
+                handler(&infer_ctx, val)?;
+                Ok(Some(()))
+            }),
+        )
+        .then(NullStore);
+    Ok(())
+}
+
+/// Build the command without derive, the values of options are read by the [`Handle`]s
+/// returned from the command after parsing.
+///
+/// ```
+/// # use soya::prelude::*;
+/// # use soya::err::Error;
+/// #
+/// # fn main() -> Result<(), Error> {
+/// let mut git = Command::new("git");
+/// let debug = git.opt::<bool>("--debug").alias("-d");
+/// let mut clone = Command::new("clone");
+/// let repo = clone.pos::<String>("repo", 1);
+/// let cloned = git.sub(clone);
+///
+/// git.parse(Args::from(vec!["git", "-d", "clone", "https://example.com/repo"]))?;
+/// assert_eq!(debug.get(), Some(true));
+/// assert_eq!(cloned.get(), Some(true));
+/// assert_eq!(repo.take(), Some("https://example.com/repo".to_owned()));
+/// # Ok(())
+/// # }
+/// ```
+pub struct Command {
+    name: String,
+    about: String,
+    opts: Vec<Box<dyn Slot>>,
+    subs: Vec<(Handle<bool>, Command)>,
+}
+
+impl std::fmt::Debug for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Command")
+            .field("name", &self.name)
+            .field("about", &self.about)
+            .field(
+                "opts",
+                &self.opts.iter().map(|v| v.info()).collect::<Vec<_>>(),
+            )
+            .field(
+                "subs",
+                &self.subs.iter().map(|(_, sub)| sub).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl Command {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            about: String::default(),
+            opts: vec![],
+            subs: vec![],
+        }
+    }
+
+    /// The description of command, it is used as the help of sub command.
+    pub fn with_about(mut self, about: impl Into<String>) -> Self {
+        self.about = about.into();
+        self
+    }
+
+    /// Add an option, the configuration is inferred from `T` same as the field of derive.
+    pub fn opt<T>(&mut self, name: impl Into<String>) -> Handle<T>
+    where
+        T: Infer,
+        T::Val: RawValParser,
+    {
+        self.add(OptInfo::new(name.into(), None), |v| v, |v| v)
+    }
+
+    /// Add a positional argument at `index`, it is inferred as [`Pos<T>`].
+    pub fn pos<T>(&mut self, name: impl Into<String>, index: usize) -> Handle<T>
+    where
+        T: Infer,
+        T::Val: RawValParser,
+    {
+        self.pos_at(name, Index::forward(index))
+    }
+
+    /// Add a positional argument at `index`, such as [`Index::range`] for the rest of arguments.
+    pub fn pos_at<T>(&mut self, name: impl Into<String>, index: Index) -> Handle<T>
+    where
+        T: Infer,
+        T::Val: RawValParser,
+    {
+        self.add::<T, Pos<T>>(OptInfo::new(name.into(), Some(index)), Pos, |v| v.0)
+    }
+
+    fn add<T, P>(&mut self, info: OptInfo, wrap: fn(T) -> P, unwrap: fn(P) -> T) -> Handle<T>
+    where
+        T: 'static,
+        P: Infer,
+        P::Val: RawValParser,
+    {
+        let handle = Handle::new(info);
+
+        self.opts.push(Box::new(TypedSlot {
+            handle: handle.clone(),
+            matched: Rc::default(),
+            wrap,
+            unwrap,
+        }));
+        handle
+    }
+
+    /// Add a sub command, it is matched by its name at the first position. The value of
+    /// returned handle is `true` if the sub command matched.
+    #[allow(clippy::should_implement_trait)]
+    pub fn sub(&mut self, sub: Command) -> Handle<bool> {
+        let mut info = OptInfo::new(sub.name.clone(), None);
+
+        info.help = sub.about.clone();
+
+        let handle = Handle::new(info);
+
+        self.subs.push((handle.clone(), sub));
+        handle
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn about(&self) -> &str {
+        &self.about
+    }
+
+    /// Reset the values of options and sub commands, so the values of last parsing are
+    /// not left in the sub command not matched.
+    fn reset(&self) {
+        for slot in self.opts.iter() {
+            slot.reset();
+        }
+        for (matched, sub) in self.subs.iter() {
+            matched.take();
+            sub.reset();
+        }
+    }
+
+    /// Create the options and sub commands, the result of matched sub command is saved
+    /// in `sub_ret`.
+    fn build<'a>(
+        &'a self,
+        failures: &'a Failures,
+        sub_ret: &'a RefCell<Option<Result<(), Error>>>,
+    ) -> Result<OptSet<'a>, Error> {
+        let mut parser = OptSet::default();
+
+        for slot in self.opts.iter() {
+            slot.register(&mut parser, failures)?;
+        }
+        for (matched, sub) in self.subs.iter() {
+            let mut cfg = SetCfg::<OptSet<'_>>::default();
+
+            matched.info.borrow().fill(&mut cfg);
+            <Cmd as Infer>::infer_fill_info(&mut cfg)?;
+
+            let mut opt = parser
+                .ctor_mut(&aopt::set::ctor_default_name())?
+                .new_with(cfg)?;
+
+            <Cmd as Infer>::infer_fill_opt(&mut opt)?;
+
+            let uid = parser.insert(opt);
+
+            parser
+                .entry(uid)?
+                .on(move |_, ctx| {
+                    let index = ctx.idx()?;
+                    let mut args: Vec<OsString> =
+                        ctx.args().iter().map(|v| v.to_os_string()).collect();
+                    let name = args.remove(index);
+
+                    // keep the command path in the program name
+                    args[0].push(" ");
+                    args[0].push(name);
+                    *matched.value.borrow_mut() = Some(true);
+                    *sub_ret.borrow_mut() = Some(sub.parse(Args::from(args)));
+                    Ok(Some(()))
+                })
+                .then(NullStore);
+        }
+        Ok(parser)
+    }
+
+    /// Describe the command and all the sub commands, such as to render the man pages.
+    pub fn describe(&self) -> Result<CmdDesc, Error> {
+        let failures = Failures::new(false);
+        let sub_ret = RefCell::new(None);
+        let parser = self.build(&failures, &sub_ret)?;
+        let mut ret = CmdDesc::new(&self.name, &parser).with_about(&self.about);
+
+        for (_, sub) in self.subs.iter() {
+            ret = ret.with_sub(sub.describe()?);
+        }
+        Ok(ret)
+    }
+
+    /// Parse the arguments and save the values into handles, the first argument is the
+    /// program name.
+    pub fn parse(&self, args: Args) -> Result<(), Error> {
+        let failures = Failures::new(false);
+        let sub_ret = RefCell::new(None);
+
+        self.reset();
+
+        let mut parser = self.build(&failures, &sub_ret)?;
+        let args = failures
+            .ok(group_values(&parser, args.clone()))
+            .unwrap_or(args);
+        let args = env_values(&parser, args);
+        let cmd = args
+            .first()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut ret = if self.subs.is_empty() {
+            parser.parse_policy(args.clone(), &mut FwdPolicy::default())?
+        } else {
+            parser.parse_policy(args.clone(), &mut PrePolicy::default())?
+        };
+        let relations = relation_failures(&parser, &args, &[])?;
+
+        drop(parser);
+        if let Some(error) = ret.take_failure() {
+            failures.push(error);
+        }
+        for failure in relations {
+            failures.push(failure);
+        }
+        failures.into_result().map_err(|e| usage_failure(&cmd, e))?;
+        for slot in self.opts.iter() {
+            slot.fill_default();
+        }
+        sub_ret.into_inner().unwrap_or(Ok(()))
+    }
+
+    pub fn parse_env(&self) -> Result<(), Error> {
+        self.parse(Args::from_env())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::ByteSize;
+
+    #[test]
+    fn reset_values_of_sub_not_matched() -> Result<(), Error> {
+        let mut git = Command::new("git");
+        let debug = git.opt::<bool>("--debug");
+        let mut clone = Command::new("clone");
+        let repo = clone.pos::<String>("repo", 1);
+        let mut add = Command::new("add");
+        let files = add.pos_at::<Vec<String>>("files", Index::range(Some(1), None));
+        let cloned = git.sub(clone);
+        let added = git.sub(add);
+
+        git.parse(Args::from(vec!["git", "--debug", "clone", "repo"]))?;
+        assert_eq!(debug.get(), Some(true));
+        assert_eq!(cloned.get(), Some(true));
+        assert_eq!(repo.get(), Some("repo".to_owned()));
+
+        git.parse(Args::from(vec!["git", "add", "a", "b"]))?;
+        assert_eq!(debug.get(), Some(false));
+        assert_eq!(cloned.get(), None);
+        assert_eq!(repo.get(), None);
+        assert_eq!(added.get(), Some(true));
+        assert_eq!(files.get(), Some(vec!["a".to_owned(), "b".to_owned()]));
+        Ok(())
+    }
+
+    #[test]
+    fn fill_default_value() -> Result<(), Error> {
+        let mut tool = Command::new("tool");
+        let size = tool
+            .opt::<ByteSize>("--size")
+            .default_value(ByteSize::new(4096));
+        let jobs = tool.opt::<Option<u64>>("--jobs").default_value(1u64);
+
+        tool.parse(Args::from(vec!["tool"]))?;
+        assert_eq!(size.get(), Some(ByteSize::new(4096)));
+        assert_eq!(jobs.get(), Some(Some(1)));
+
+        tool.parse(Args::from(vec!["tool", "--size=1k", "--jobs=2"]))?;
+        assert_eq!(size.get(), Some(ByteSize::new(1000)));
+        assert_eq!(jobs.get(), Some(Some(2)));
+        Ok(())
+    }
+
+    #[test]
+    fn report_failure_of_sub() {
+        let mut git = Command::new("git");
+        let mut clone = Command::new("clone");
+        let _ = clone.pos::<u64>("depth", 1);
+        let _ = git.sub(clone);
+
+        let error = git
+            .parse(Args::from(vec!["git", "clone", "x"]))
+            .unwrap_err();
+
+        assert!(error.to_string().starts_with("git clone: "), "{}", error);
+    }
+}
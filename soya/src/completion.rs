@@ -0,0 +1,851 @@
+use std::ffi::OsString;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Write;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use aopt::opt::Action;
+use aopt::opt::Index;
+use aopt::opt::Style;
+
+use crate::arity::like_option;
+use crate::describe::CmdDesc;
+use crate::err::err;
+use crate::infer::Infer;
+use crate::infer::OptSpec;
+use crate::token::Takes;
+use crate::token::Token;
+use crate::token::Tokens;
+use crate::value::invalid_value;
+use crate::Error;
+
+/// The environment variable set by the shell when calling the program for candidates.
+pub const COMPLETE_ENV: &str = "SOYA_COMPLETE";
+
+/// The hidden flag print the completion script of shell, see
+/// [`ParserImpl::generate`](crate::ParserImpl::generate).
+pub const GENERATE_COMPLETION: &str = "--generate-completion";
+
+/// The kind of value the shell should complete for an option or positional argument.
+///
+/// Only [`PathBuf`](std::path::PathBuf) is inferred as [`File`](ValueHint::File), a path can
+/// not tell a directory from a file by its type. Set [`Dir`](ValueHint::Dir) for the directory
+/// option by [`set_value_hint`](crate::opt::Opt::set_value_hint) or
+/// [`with_value_hint`](crate::infer::OptSpec::with_value_hint) of its value type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValueHint {
+    #[default]
+    Any,
+
+    File,
+
+    Dir,
+}
+
+/// The shells supported by [`Completion::generate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+
+    Zsh,
+
+    Fish,
+}
+
+impl Shell {
+    pub const NAMES: [&'static str; 3] = ["bash", "zsh", "fish"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+}
+
+impl Display for Shell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Shell {
+    type Err = Error;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        match val {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            _ => Err(invalid_value(
+                val,
+                format!("expected one of: {}", Self::NAMES.join(", ")),
+            )),
+        }
+    }
+}
+
+impl Infer for Shell {
+    type Val = String;
+
+    fn infer_spec() -> OptSpec {
+        OptSpec::new::<Self>()
+            .with_possible_values(Some(Self::NAMES.iter().map(|v| v.to_string()).collect()))
+            .with_value_name(Some("<shell>".to_owned()))
+    }
+
+    fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        val?.parse()
+    }
+
+    fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+        *self = Self::infer_map(val)?;
+        Ok(())
+    }
+}
+
+/// The callback return the candidates of the word being completed.
+pub type CompleteFn = dyn Fn(&str) -> Vec<String>;
+
+#[derive(Clone)]
+struct Completer(Rc<CompleteFn>);
+
+impl Debug for Completer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Completer").finish()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct CompleteOpt {
+    names: Vec<String>,
+    help: String,
+    index: Option<Index>,
+    takes_value: bool,
+    multiple: bool,
+    possible_values: Option<Vec<String>>,
+    value_hint: ValueHint,
+    takes: Option<Takes>,
+    complete: Option<Completer>,
+}
+
+impl CompleteOpt {
+    /// The candidates of value, the callback is preferred over the possible values and hint.
+    fn candidates(&self, current: &str) -> Vec<String> {
+        if let Some(complete) = &self.complete {
+            (complete.0)(current)
+        } else if let Some(values) = &self.possible_values {
+            values.clone()
+        } else {
+            match self.value_hint {
+                ValueHint::Any => vec![],
+                ValueHint::File => complete_path(current, false),
+                ValueHint::Dir => complete_path(current, true),
+            }
+        }
+    }
+}
+
+/// The description of a command used to generate completion script, it is built from the
+/// [`CmdDesc`] of command tree.
+/// The option or sub command found when tokenizing the words.
+#[derive(Debug, Clone, Copy)]
+enum Found<'a> {
+    Opt(&'a CompleteOpt),
+
+    Sub(&'a Completion),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Completion {
+    name: String,
+    help: String,
+    opts: Vec<CompleteOpt>,
+    poss: Vec<CompleteOpt>,
+    subs: Vec<Completion>,
+}
+
+impl Completion {
+    pub fn new(desc: &CmdDesc) -> Self {
+        Self {
+            name: desc.name().to_owned(),
+            help: desc.about().lines().next().unwrap_or_default().to_owned(),
+            opts: desc
+                .opts()
+                .iter()
+                .map(|opt| {
+                    let mut names = vec![opt.name().to_owned()];
+
+                    names.extend(opt.aliases().iter().cloned());
+                    CompleteOpt {
+                        names,
+                        help: opt.help().to_owned(),
+                        takes_value: !opt.mat_style(Style::Boolean),
+                        multiple: opt.action() == Action::App,
+                        possible_values: opt.possible_values().map(<[String]>::to_vec),
+                        value_hint: opt.value_hint(),
+                        takes: Some(Takes::new(opt.styles(), opt.arity())),
+                        ..Default::default()
+                    }
+                })
+                .collect(),
+            poss: desc
+                .poss()
+                .iter()
+                .map(|pos| CompleteOpt {
+                    names: vec![pos.name().to_owned()],
+                    help: pos.help().to_owned(),
+                    index: pos.index().cloned(),
+                    possible_values: pos.possible_values().map(<[String]>::to_vec),
+                    value_hint: pos.value_hint(),
+                    ..Default::default()
+                })
+                .collect(),
+            subs: desc.subs().iter().map(Self::new).collect(),
+        }
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = help.into();
+        self
+    }
+
+    /// Find the sub command `name`, such as to set the callback of its options.
+    pub fn with_sub<F>(mut self, name: &str, f: F) -> Result<Self, Error>
+    where
+        F: FnOnce(Completion) -> Result<Completion, Error>,
+    {
+        let sub = self
+            .subs
+            .iter_mut()
+            .find(|sub| sub.name == name)
+            .ok_or_else(|| err!("can not find sub command `{}` in completion", name))?;
+
+        *sub = f(std::mem::take(sub))?;
+        Ok(self)
+    }
+
+    /// Set the callback return the candidates of option or positional argument `name`,
+    /// it is called with the word being completed.
+    pub fn with_complete<F>(mut self, name: &str, complete: F) -> Result<Self, Error>
+    where
+        F: Fn(&str) -> Vec<String> + 'static,
+    {
+        let opt = self
+            .opts
+            .iter_mut()
+            .chain(self.poss.iter_mut())
+            .find(|opt| opt.names.iter().any(|v| v == name))
+            .ok_or_else(|| err!("can not find option `{}` in completion", name))?;
+
+        opt.complete = Some(Completer(Rc::new(complete)));
+        Ok(self)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn help(&self) -> &str {
+        &self.help
+    }
+
+    pub fn subs(&self) -> &[Completion] {
+        &self.subs
+    }
+
+    /// Generate the completion script of `shell`.
+    pub fn generate(&self, shell: Shell) -> String {
+        match shell {
+            Shell::Bash => self.gen_bash(),
+            Shell::Zsh => self.gen_zsh(),
+            Shell::Fish => self.gen_fish(),
+        }
+    }
+
+    /// The hint of the positional arguments, used by the static scripts.
+    fn args_hint(&self) -> ValueHint {
+        self.poss
+            .iter()
+            .map(|pos| pos.value_hint)
+            .find(|hint| hint != &ValueHint::Any)
+            .unwrap_or_default()
+    }
+
+    fn find_opt(&self, name: &str) -> Option<&CompleteOpt> {
+        self.opts
+            .iter()
+            .find(|opt| opt.names.iter().any(|v| v == name))
+    }
+
+    fn find(&self, name: &str) -> Option<(Found<'_>, Takes)> {
+        match self.find_opt(name) {
+            Some(opt) => opt.takes.map(|takes| (Found::Opt(opt), takes)),
+            None => self
+                .subs
+                .iter()
+                .find(|sub| sub.name == name)
+                .map(|sub| (Found::Sub(sub), Takes::Cmd)),
+        }
+    }
+
+    /// Return the candidates of the last word in `words`, the first word is the program name.
+    ///
+    /// The words are split the same as the parser does, see [`group_values`](crate::arity::group_values):
+    /// the option consumes its values by the arity, the words after `--` are positional
+    /// arguments, and the unknown options are skipped.
+    pub fn complete<T: AsRef<str>>(&self, words: &[T]) -> Vec<String> {
+        let (current, done) = match words.split_last() {
+            Some((current, done)) => (current.as_ref(), done),
+            None => ("", &[][..]),
+        };
+        let done: Vec<OsString> = done
+            .iter()
+            .skip(1)
+            .map(|v| OsString::from(v.as_ref()))
+            .collect();
+        let mut tokens = Tokens::new(&done);
+        let mut cmd = self;
+        // the last option waiting for values and the count of values it has
+        let mut pending: Option<(&CompleteOpt, usize)> = None;
+        let mut noa_index = 0;
+
+        while let Some((token, _)) = tokens.next_token(|name| cmd.find(name)) {
+            pending = None;
+            match token {
+                Token::Opt {
+                    opt: Found::Opt(opt),
+                    values,
+                    ..
+                } => {
+                    let count = values.len();
+                    let (_, max) = opt.takes.map(|v| v.value_range()).unwrap_or_default();
+
+                    pending = (count < max).then_some((opt, count));
+                }
+                Token::Cmd(Found::Sub(sub)) => {
+                    cmd = sub;
+                    noa_index = 0;
+                }
+                Token::Arg(word) if tokens.stopped() || !word.to_str().is_some_and(like_option) => {
+                    noa_index += 1;
+                }
+                _ => {}
+            }
+        }
+
+        let mut candidates = match pending {
+            Some((opt, count))
+                if count < opt.takes.map(|v| v.value_range().0).unwrap_or_default()
+                    || !like_option(current) =>
+            {
+                opt.candidates(current)
+            }
+            _ if tokens.stopped() => cmd.pos_candidates(noa_index + 1, current),
+            _ => {
+                if let Some((name, value)) = current
+                    .split_once('=')
+                    .filter(|(name, _)| name.starts_with('-'))
+                {
+                    cmd.find_opt(name)
+                        .map(|opt| opt.candidates(value))
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|v| format!("{}={}", name, v))
+                        .collect()
+                } else if current.starts_with('-') {
+                    cmd.opts
+                        .iter()
+                        .flat_map(|opt| opt.names.iter().cloned())
+                        .collect()
+                } else {
+                    let mut candidates: Vec<_> =
+                        cmd.subs.iter().map(|sub| sub.name.clone()).collect();
+
+                    candidates.extend(cmd.pos_candidates(noa_index + 1, current));
+                    candidates
+                }
+            }
+        };
+
+        candidates.retain(|v| v.starts_with(current));
+        candidates.sort();
+        candidates.dedup();
+        candidates
+    }
+
+    /// The candidates of the positional arguments at `noa_index`.
+    fn pos_candidates(&self, noa_index: usize, current: &str) -> Vec<String> {
+        self.poss
+            .iter()
+            .filter(|pos| {
+                pos.index
+                    .as_ref()
+                    .and_then(|index| index.calc_index(noa_index, noa_index + 1))
+                    == Some(noa_index)
+            })
+            .flat_map(|pos| pos.candidates(current))
+            .collect()
+    }
+
+    /// Generate the script makes the shell call the program for candidates,
+    /// see [`complete_env`].
+    pub fn generate_dynamic(&self, shell: Shell) -> String {
+        let root = ident(&self.name);
+        let mut out = String::new();
+
+        match shell {
+            Shell::Bash => {
+                let _ = writeln!(out, "_{}() {{", root);
+                out.push_str("    local IFS=$'\\n'\n");
+                let _ = writeln!(
+                    out,
+                    "    COMPREPLY=($({}=\"${{COMP_CWORD}}\" \"${{COMP_WORDS[0]}}\" \"${{COMP_WORDS[@]}}\" 2>/dev/null))",
+                    COMPLETE_ENV
+                );
+                out.push_str("}\n\n");
+                let _ = writeln!(out, "complete -F _{} {}", root, self.name);
+            }
+            Shell::Zsh => {
+                let _ = writeln!(out, "#compdef {}\n", self.name);
+                let _ = writeln!(out, "_{}() {{", root);
+                out.push_str("    local -a candidates\n\n");
+                let _ = writeln!(
+                    out,
+                    "    candidates=(\"${{(@f)$({}=$((CURRENT - 1)) \"${{words[1]}}\" \"${{words[@]}}\" 2>/dev/null)}}\")",
+                    COMPLETE_ENV
+                );
+                out.push_str("    compadd -a candidates\n");
+                out.push_str("}\n\n");
+                let _ = writeln!(out, "compdef _{} {}", root, self.name);
+            }
+            Shell::Fish => {
+                let _ = writeln!(out, "function __{}_complete", root);
+                out.push_str("    set -l words (commandline -opc) (commandline -ct)\n");
+                let _ = writeln!(
+                    out,
+                    "    {}=(math (count $words) - 1) $words[1] $words 2>/dev/null",
+                    COMPLETE_ENV
+                );
+                out.push_str("end\n\n");
+                let _ = writeln!(
+                    out,
+                    "complete -c {} -f -a '(__{}_complete)'",
+                    self.name, root
+                );
+            }
+        }
+        out
+    }
+
+    fn walk<'a, F>(&'a self, path: &str, f: &mut F)
+    where
+        F: FnMut(&str, &'a Completion),
+    {
+        f(path, self);
+        for sub in self.subs.iter() {
+            sub.walk(&format!("{}__{}", path, ident(&sub.name)), f);
+        }
+    }
+
+    fn words(&self) -> Vec<&str> {
+        self.opts
+            .iter()
+            .flat_map(|opt| opt.names.iter())
+            .chain(self.subs.iter().map(|sub| &sub.name))
+            .map(String::as_str)
+            .collect()
+    }
+
+    fn gen_bash(&self) -> String {
+        let root = ident(&self.name);
+        let mut out = String::new();
+
+        let _ = writeln!(out, "_{}() {{", root);
+        out.push_str("    local cur prev cmd i\n");
+        out.push_str("    COMPREPLY=()\n");
+        out.push_str("    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+        out.push_str("    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+        let _ = writeln!(out, "    cmd=\"{}\"\n", root);
+        out.push_str("    for ((i = 1; i < COMP_CWORD; i++)); do\n");
+        out.push_str("        case \"${cmd}__${COMP_WORDS[i]}\" in\n");
+        self.walk(&root, &mut |path, cmd| {
+            for sub in cmd.subs.iter() {
+                let _ = writeln!(
+                    out,
+                    "            \"{}__{}\") cmd=\"{}__{}\" ;;",
+                    path,
+                    sub.name,
+                    path,
+                    ident(&sub.name)
+                );
+            }
+        });
+        out.push_str("        esac\n");
+        out.push_str("    done\n\n");
+        out.push_str("    case \"${cmd}\" in\n");
+        self.walk(&root, &mut |path, cmd| {
+            let _ = writeln!(out, "        {})", path);
+            if cmd.opts.iter().any(|opt| opt.takes_value) {
+                out.push_str("            case \"${prev}\" in\n");
+                for opt in cmd.opts.iter().filter(|opt| opt.takes_value) {
+                    let reply = match (&opt.possible_values, opt.value_hint) {
+                        (Some(values), _) => {
+                            format!(
+                                "COMPREPLY=($(compgen -W \"{}\" -- \"${{cur}}\"))",
+                                values.join(" ")
+                            )
+                        }
+                        (None, ValueHint::File) => {
+                            "COMPREPLY=($(compgen -f -- \"${cur}\"))".to_owned()
+                        }
+                        (None, ValueHint::Dir) => {
+                            "COMPREPLY=($(compgen -d -- \"${cur}\"))".to_owned()
+                        }
+                        (None, ValueHint::Any) => "COMPREPLY=()".to_owned(),
+                    };
+
+                    let _ = writeln!(out, "                {})", opt.names.join(" | "));
+                    let _ = writeln!(out, "                    {}", reply);
+                    out.push_str("                    return 0\n");
+                    out.push_str("                    ;;\n");
+                }
+                out.push_str("            esac\n");
+            }
+            let _ = writeln!(
+                out,
+                "            COMPREPLY=($(compgen -W \"{}\" -- \"${{cur}}\"))",
+                cmd.words().join(" ")
+            );
+            if let Some(flag) = match cmd.args_hint() {
+                ValueHint::File => Some("-f"),
+                ValueHint::Dir => Some("-d"),
+                ValueHint::Any => None,
+            } {
+                out.push_str("            if [[ \"${cur}\" != -* ]]; then\n");
+                let _ = writeln!(
+                    out,
+                    "                COMPREPLY+=($(compgen {} -- \"${{cur}}\"))",
+                    flag
+                );
+                out.push_str("            fi\n");
+            }
+            out.push_str("            ;;\n");
+        });
+        out.push_str("    esac\n");
+        out.push_str("}\n\n");
+        let _ = writeln!(out, "complete -F _{} {}", root, self.name);
+        out
+    }
+
+    fn gen_zsh(&self) -> String {
+        let root = ident(&self.name);
+        let mut out = format!("#compdef {}\n", self.name);
+
+        self.walk(&root, &mut |path, cmd| {
+            let mut specs = vec![];
+
+            for opt in cmd.opts.iter() {
+                let repeat = if opt.multiple { "*" } else { "" };
+                let value = if opt.takes_value {
+                    let action = match (&opt.possible_values, opt.value_hint) {
+                        (Some(values), _) => format!("({})", values.join(" ")),
+                        (None, ValueHint::File) => "_files".to_owned(),
+                        (None, ValueHint::Dir) => "_files -/".to_owned(),
+                        (None, ValueHint::Any) => String::default(),
+                    };
+
+                    format!(":{}:{}", opt.names[0].trim_start_matches('-'), action)
+                } else {
+                    String::default()
+                };
+
+                let help = if opt.help.is_empty() {
+                    String::default()
+                } else {
+                    format!("[{}]", zsh_escape(&opt.help))
+                };
+
+                for name in opt.names.iter() {
+                    specs.push(format!("'{}{}{}{}'", repeat, name, help, value));
+                }
+            }
+            if cmd.subs.is_empty() {
+                match cmd.args_hint() {
+                    ValueHint::File => specs.push("'*:file:_files'".to_owned()),
+                    ValueHint::Dir => specs.push("'*:directory:_files -/'".to_owned()),
+                    ValueHint::Any => {}
+                }
+            } else {
+                specs.push("'1: :->cmds'".to_owned());
+                specs.push("'*:: :->args'".to_owned());
+            }
+
+            let _ = writeln!(out, "\n_{}() {{", path);
+            if cmd.subs.is_empty() {
+                out.push_str("    _arguments");
+            } else {
+                out.push_str("    local curcontext=\"$curcontext\" state line\n");
+                out.push_str("    local -a commands\n\n");
+                out.push_str("    _arguments -C");
+            }
+            for spec in specs {
+                let _ = write!(out, " \\\n        {}", spec);
+            }
+            out.push('\n');
+            if !cmd.subs.is_empty() {
+                out.push_str("\n    case $state in\n");
+                out.push_str("        cmds)\n");
+                out.push_str("            commands=(\n");
+                for sub in cmd.subs.iter() {
+                    let _ = writeln!(
+                        out,
+                        "                '{}:{}'",
+                        sub.name,
+                        zsh_escape(&sub.help).replace(':', "\\:")
+                    );
+                }
+                out.push_str("            )\n");
+                out.push_str("            _describe -t commands 'command' commands\n");
+                out.push_str("            ;;\n");
+                out.push_str("        args)\n");
+                out.push_str("            case $line[1] in\n");
+                for sub in cmd.subs.iter() {
+                    let _ = writeln!(
+                        out,
+                        "                {}) _{}__{} ;;",
+                        sub.name,
+                        path,
+                        ident(&sub.name)
+                    );
+                }
+                out.push_str("            esac\n");
+                out.push_str("            ;;\n");
+                out.push_str("    esac\n");
+            }
+            out.push_str("}\n");
+        });
+        let _ = write!(
+            out,
+            "\nif [ \"$funcstack[1]\" = \"_{root}\" ]; then\n    _{root} \"$@\"\nelse\n    compdef _{root} {}\nfi\n",
+            self.name,
+            root = root
+        );
+        out
+    }
+
+    fn gen_fish(&self) -> String {
+        let mut out = String::new();
+        let mut gen = |cmd: &Completion, cond: String| {
+            let prefix = format!("complete -c {}{}", self.name, cond);
+
+            for opt in cmd.opts.iter() {
+                let mut line = prefix.clone();
+
+                for name in opt.names.iter() {
+                    if let Some(long) = name.strip_prefix("--") {
+                        let _ = write!(line, " -l {}", long);
+                    } else if let Some(short) = name.strip_prefix('-') {
+                        let flag = if short.chars().count() == 1 { "s" } else { "o" };
+
+                        let _ = write!(line, " -{} {}", flag, short);
+                    }
+                }
+                if opt.takes_value {
+                    match (&opt.possible_values, opt.value_hint) {
+                        (Some(values), _) => {
+                            let _ = write!(line, " -r -f -a '{}'", fish_escape(&values.join(" ")));
+                        }
+                        (None, ValueHint::File) => line.push_str(" -r -F"),
+                        (None, ValueHint::Dir) => {
+                            line.push_str(" -r -f -a '(__fish_complete_directories)'")
+                        }
+                        (None, ValueHint::Any) => line.push_str(" -r -f"),
+                    }
+                }
+                if !opt.help.is_empty() {
+                    let _ = write!(line, " -d '{}'", fish_escape(&opt.help));
+                }
+                let _ = writeln!(out, "{}", line);
+            }
+            for sub in cmd.subs.iter() {
+                let _ = write!(out, "{} -f -a '{}'", prefix, fish_escape(&sub.name));
+                if !sub.help.is_empty() {
+                    let _ = write!(out, " -d '{}'", fish_escape(&sub.help));
+                }
+                out.push('\n');
+            }
+            if cmd.args_hint() == ValueHint::Dir {
+                let _ = writeln!(out, "{} -f -a '(__fish_complete_directories)'", prefix);
+            }
+        };
+        let subs_of = |cmd: &Completion| {
+            cmd.subs
+                .iter()
+                .map(|sub| sub.name.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        if self.subs.is_empty() {
+            gen(self, String::default());
+        } else {
+            gen(self, " -n '__fish_use_subcommand'".to_owned());
+        }
+        self.walk("", &mut |path, cmd| {
+            if !path.is_empty() {
+                let cond = if cmd.subs.is_empty() {
+                    format!(" -n '__fish_seen_subcommand_from {}'", cmd.name)
+                } else {
+                    format!(
+                        " -n '__fish_seen_subcommand_from {}; and not __fish_seen_subcommand_from {}'",
+                        cmd.name,
+                        subs_of(cmd)
+                    )
+                };
+
+                gen(cmd, cond);
+            }
+        });
+        out
+    }
+}
+
+/// Print the candidates and return `true` if the program is called by the script of
+/// [`Completion::generate_dynamic`].
+///
+/// The shell set [`COMPLETE_ENV`] to the index of the word being completed and passes all
+/// the words of command line as arguments, the first one is the program name.
+pub fn complete_env(completion: &Completion) -> bool {
+    match std::env::var(COMPLETE_ENV) {
+        Ok(cword) => {
+            let cword = cword.parse::<usize>().unwrap_or(usize::MAX);
+            let mut words: Vec<String> = std::env::args().skip(1).take(cword + 1).collect();
+
+            if words.len() <= cword {
+                words.push(String::default());
+            }
+            for candidate in completion.complete(&words) {
+                println!("{}", candidate);
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// List the paths start with `current`, only the directories are listed if `dir_only`.
+fn complete_path(current: &str, dir_only: bool) -> Vec<String> {
+    let (dir, prefix) = match current.rfind('/') {
+        Some(i) => current.split_at(i + 1),
+        None => ("", current),
+    };
+    let mut ret = vec![];
+
+    if let Ok(entries) = std::fs::read_dir(if dir.is_empty() { "." } else { dir }) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.path().is_dir();
+
+            if name.starts_with(prefix) && (is_dir || !dir_only) {
+                ret.push(format!("{}{}{}", dir, name, if is_dir { "/" } else { "" }));
+            }
+        }
+    }
+    ret.sort();
+    ret
+}
+
+/// Convert the command name to the name can be used in shell function.
+fn ident(name: &str) -> String {
+    name.chars()
+        .map(|ch| if ch.is_alphanumeric() { ch } else { '_' })
+        .collect()
+}
+
+fn zsh_escape(val: &str) -> String {
+    val.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+fn fish_escape(val: &str) -> String {
+    val.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Command;
+    use crate::value::Multi;
+
+    fn completion() -> Result<Completion, Error> {
+        let mut tool = Command::new("tool");
+        let _ = tool.opt::<Option<(u64, u64)>>("--point");
+        let _ = tool.opt::<Option<Multi<String, 1, 3>>>("--tag");
+        let _ = tool.opt::<Option<Shell>>("--shell").alias("--sh");
+        let _ = tool.opt::<bool>("--debug");
+        let _ = tool.pos::<Option<Shell>>("target", 1);
+        let mut run = Command::new("run");
+        let _ = run.opt::<bool>("--fast");
+        let _ = tool.sub(run);
+
+        Ok(Completion::new(&tool.describe()?))
+    }
+
+    #[test]
+    fn consume_values_of_arity() -> Result<(), Error> {
+        let cmp = completion()?;
+
+        // `--point` takes two values, the second one is not the sub command
+        assert_eq!(
+            cmp.complete(&["tool", "--point", "1", "r"]),
+            Vec::<String>::new()
+        );
+        assert_eq!(cmp.complete(&["tool", "--point", "1", "2", "r"]), ["run"]);
+        assert_eq!(cmp.complete(&["tool", "--point=1", "2", "r"]), ["run"]);
+        // `--tag` takes one to three values, stop at the option
+        assert_eq!(
+            cmp.complete(&["tool", "--tag", "a", "--debug", "r"]),
+            ["run"]
+        );
+        assert_eq!(
+            cmp.complete(&["tool", "--tag", "a", "--sh"]),
+            ["--sh", "--shell"]
+        );
+        assert_eq!(cmp.complete(&["tool", "--shell", "z"]), ["zsh"]);
+        // the alias takes the value same as the option
+        assert_eq!(cmp.complete(&["tool", "--sh", "bash", "r"]), ["run"]);
+        Ok(())
+    }
+
+    #[test]
+    fn stop_options_at_double_dash() -> Result<(), Error> {
+        let cmp = completion()?;
+
+        assert_eq!(cmp.complete(&["tool", "--", "--de"]), Vec::<String>::new());
+        assert_eq!(cmp.complete(&["tool", "--", "b"]), ["bash"]);
+        // the sub command is not matched after `--`
+        assert_eq!(
+            cmp.complete(&["tool", "--", "run", "--f"]),
+            Vec::<String>::new()
+        );
+        assert_eq!(cmp.complete(&["tool", "run", "--f"]), ["--fast"]);
+        Ok(())
+    }
+
+    #[test]
+    fn sort_and_dedup_candidates() -> Result<(), Error> {
+        let cmp = completion()?.with_complete("target", |_| {
+            vec!["b".to_owned(), "a".to_owned(), "b".to_owned()]
+        })?;
+
+        assert_eq!(cmp.complete(&["tool", ""]), ["a", "b", "run"]);
+        Ok(())
+    }
+}
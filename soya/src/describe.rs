@@ -0,0 +1,210 @@
+use std::ops::RangeInclusive;
+
+use aopt::opt::Action;
+use aopt::opt::Index;
+use aopt::opt::Opt as _;
+use aopt::opt::Style;
+use aopt::set::Ctor;
+use aopt::set::Set;
+
+use crate::completion::ValueHint;
+use crate::help::display_usage;
+use crate::opt::Opt;
+
+/// The information of an option or positional argument in [`CmdDesc`].
+#[derive(Debug, Clone)]
+pub struct OptDesc {
+    name: String,
+    aliases: Vec<String>,
+    hint: String,
+    help: String,
+    styles: Vec<Style>,
+    index: Option<Index>,
+    force: bool,
+    action: Action,
+    value_name: Option<String>,
+    default_value: Option<String>,
+    env: Option<String>,
+    possible_values: Option<Vec<String>>,
+    value_hint: ValueHint,
+    arity: Option<RangeInclusive<usize>>,
+}
+
+impl OptDesc {
+    pub fn new(opt: &Opt) -> Self {
+        Self {
+            name: opt.name().to_owned(),
+            aliases: opt.alias().cloned().unwrap_or_default(),
+            hint: opt.hint().to_owned(),
+            help: opt.help().to_owned(),
+            styles: opt.styles().to_vec(),
+            index: opt.index().cloned(),
+            force: opt.force(),
+            action: *opt.action(),
+            value_name: opt.value_name().map(String::from),
+            default_value: opt.default_value().map(String::from),
+            env: opt.env().map(String::from),
+            possible_values: opt.possible_values().map(<[String]>::to_vec),
+            value_hint: opt.value_hint(),
+            arity: opt.arity().cloned(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    /// The names and value of option displayed in help, such as `--depth <u64>`.
+    pub fn hint(&self) -> &str {
+        &self.hint
+    }
+
+    pub fn help(&self) -> &str {
+        &self.help
+    }
+
+    pub fn styles(&self) -> &[Style] {
+        &self.styles
+    }
+
+    pub fn mat_style(&self, style: Style) -> bool {
+        self.styles.contains(&style)
+    }
+
+    pub fn index(&self) -> Option<&Index> {
+        self.index.as_ref()
+    }
+
+    pub fn force(&self) -> bool {
+        self.force
+    }
+
+    pub fn action(&self) -> Action {
+        self.action
+    }
+
+    pub fn value_name(&self) -> Option<&str> {
+        self.value_name.as_deref()
+    }
+
+    pub fn default_value(&self) -> Option<&str> {
+        self.default_value.as_deref()
+    }
+
+    pub fn env(&self) -> Option<&str> {
+        self.env.as_deref()
+    }
+
+    pub fn possible_values(&self) -> Option<&[String]> {
+        self.possible_values.as_deref()
+    }
+
+    pub fn value_hint(&self) -> ValueHint {
+        self.value_hint
+    }
+
+    pub fn arity(&self) -> Option<&RangeInclusive<usize>> {
+        self.arity.as_ref()
+    }
+}
+
+/// The description of a command tree shared by the [`Manual`](crate::man::Manual),
+/// [`Reference`](crate::reference::Reference), [`Schema`](crate::schema::Schema) and
+/// [`Completion`](crate::completion::Completion).
+///
+/// The options are collected from the option set same as the help message, the sub commands
+/// collected only have the name and help, they are replaced by [`with_sub`](CmdDesc::with_sub).
+#[derive(Debug, Clone, Default)]
+pub struct CmdDesc {
+    name: String,
+    about: String,
+    usage: String,
+    opts: Vec<OptDesc>,
+    poss: Vec<OptDesc>,
+    subs: Vec<CmdDesc>,
+}
+
+impl CmdDesc {
+    /// Collect the options of command `name` from `set`, the hidden options are skipped.
+    pub fn new<S>(name: impl Into<String>, set: &S) -> Self
+    where
+        S: Set,
+        S::Ctor: Ctor<Opt = Opt>,
+    {
+        let mut ret = Self {
+            name: name.into(),
+            usage: display_usage(set, "").trim_start().to_owned(),
+            ..Default::default()
+        };
+
+        for opt in set.iter().filter(|opt| !opt.hidden()) {
+            if opt.mat_style(Style::Cmd) {
+                ret.subs.push(Self {
+                    name: opt.name().to_owned(),
+                    about: opt.help().to_owned(),
+                    ..Default::default()
+                });
+            } else if opt.mat_style(Style::Pos) {
+                ret.poss.push(OptDesc::new(opt));
+            } else if !opt.mat_style(Style::Main) {
+                ret.opts.push(OptDesc::new(opt));
+            }
+        }
+        ret.poss.sort_by_key(|pos| match pos.index() {
+            Some(Index::Forward(index)) => *index,
+            Some(Index::Range(start, _)) => *start,
+            _ => usize::MAX,
+        });
+        ret
+    }
+
+    /// The description of command, such as the document of struct.
+    pub fn with_about(mut self, about: impl Into<String>) -> Self {
+        self.about = about.into();
+        self
+    }
+
+    /// Add the sub command, it replaces the sub command collected from the option set with same name.
+    pub fn with_sub(mut self, mut sub: CmdDesc) -> Self {
+        match self.subs.iter_mut().find(|v| v.name == sub.name) {
+            Some(exist) => {
+                if sub.about.is_empty() {
+                    sub.about = std::mem::take(&mut exist.about);
+                }
+                *exist = sub;
+            }
+            None => self.subs.push(sub),
+        }
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn about(&self) -> &str {
+        &self.about
+    }
+
+    /// The usage line without the command name, such as `[OPTIONS] <repo> [dir]`.
+    pub fn usage(&self) -> &str {
+        &self.usage
+    }
+
+    pub fn opts(&self) -> &[OptDesc] {
+        &self.opts
+    }
+
+    /// The positional arguments in the order of index.
+    pub fn poss(&self) -> &[OptDesc] {
+        &self.poss
+    }
+
+    pub fn subs(&self) -> &[CmdDesc] {
+        &self.subs
+    }
+}
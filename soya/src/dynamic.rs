@@ -0,0 +1,618 @@
+use std::collections::BTreeMap;
+
+use aopt::opt::Index;
+use aopt::value::RawValParser;
+
+use crate::builder::Command;
+use crate::builder::Handle;
+use crate::err::err;
+use crate::infer::Infer;
+use crate::json::Json;
+use crate::prelude::Args;
+use crate::Error;
+
+/// The kind of value of option defined at runtime.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValueKind {
+    Bool,
+
+    #[default]
+    Str,
+
+    Int,
+
+    Float,
+
+    /// The option can be set multiple times, all the values are kept as strings.
+    List,
+}
+
+impl ValueKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueKind::Bool => "bool",
+            ValueKind::Str => "string",
+            ValueKind::Int => "int",
+            ValueKind::Float => "float",
+            ValueKind::List => "list",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bool" => Some(ValueKind::Bool),
+            "string" => Some(ValueKind::Str),
+            "int" => Some(ValueKind::Int),
+            "float" => Some(ValueKind::Float),
+            "list" => Some(ValueKind::List),
+            _ => None,
+        }
+    }
+}
+
+/// The value of option parsed by [`DynCommand`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+
+    Str(String),
+
+    Int(i64),
+
+    Float(f64),
+
+    List(Vec<String>),
+}
+
+/// The values parsed by [`DynCommand`], keyed by the option name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedValues {
+    values: BTreeMap<String, Value>,
+    sub: Option<(String, Box<ParsedValues>)>,
+}
+
+impl ParsedValues {
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.get(name)? {
+            Value::Bool(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            Value::Str(val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn get_int(&self, name: &str) -> Option<i64> {
+        match self.get(name)? {
+            Value::Int(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    pub fn get_float(&self, name: &str) -> Option<f64> {
+        match self.get(name)? {
+            Value::Float(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    pub fn get_list(&self, name: &str) -> Option<&[String]> {
+        match self.get(name)? {
+            Value::List(vals) => Some(vals),
+            _ => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// The sub command matched and its values.
+    pub fn sub(&self) -> Option<(&str, &ParsedValues)> {
+        self.sub
+            .as_ref()
+            .map(|(name, values)| (name.as_str(), &**values))
+    }
+}
+
+/// The option of [`DynCommand`].
+#[derive(Debug, Clone, Default)]
+pub struct DynOpt {
+    name: String,
+    aliases: Vec<String>,
+    kind: ValueKind,
+    index: Option<Index>,
+    force: bool,
+    help: String,
+    env: Option<String>,
+}
+
+impl DynOpt {
+    pub fn new(name: impl Into<String>, kind: ValueKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    /// The option with an index is a positional argument.
+    pub fn with_index(mut self, index: Option<Index>) -> Self {
+        self.index = index;
+        self
+    }
+
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = help.into();
+        self
+    }
+
+    pub fn with_env(mut self, env: Option<String>) -> Self {
+        self.env = env;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> ValueKind {
+        self.kind
+    }
+
+    pub fn index(&self) -> Option<&Index> {
+        self.index.as_ref()
+    }
+
+    /// Add the option into `cmd` as the type `T` of kind.
+    fn add<T>(&self, cmd: &mut Command) -> Handle<T>
+    where
+        T: Infer,
+        T::Val: RawValParser,
+    {
+        let mut handle = match &self.index {
+            Some(index) => cmd.pos_at::<T>(&self.name, index.clone()),
+            None => cmd.opt::<T>(&self.name),
+        };
+
+        for alias in self.aliases.iter() {
+            handle = handle.alias(alias);
+        }
+        if let Some(env) = &self.env {
+            handle = handle.env(env);
+        }
+        handle.force(self.force).help(&self.help)
+    }
+
+    /// Read the option from the JSON object, the `kind` is guessed from the `styles`,
+    /// `action` and `value` if it is not present, so that the output of
+    /// [`Schema`](crate::schema::Schema) can be used directly.
+    pub fn from_json(json: &Json) -> Result<Self, Error> {
+        let name = json
+            .get("name")
+            .and_then(Json::as_str)
+            .ok_or_else(|| err!("invalid schema: option must have a `name`"))?;
+        let strs = |key: &str| -> Vec<&str> {
+            json.get(key)
+                .and_then(Json::as_array)
+                .map(|vals| vals.iter().filter_map(Json::as_str).collect())
+                .unwrap_or_default()
+        };
+        let styles = strs("styles");
+        let kind = match json.get("kind").and_then(Json::as_str) {
+            Some(kind) => ValueKind::from_name(kind).ok_or_else(|| {
+                err!(
+                    "invalid schema: unknown kind `{}` of option `{}`",
+                    kind,
+                    name
+                )
+            })?,
+            None if styles.iter().any(|v| *v == "Boolean" || *v == "Flag") => ValueKind::Bool,
+            None if json.get("action").and_then(Json::as_str) == Some("App") => ValueKind::List,
+            None => match json.get("value").and_then(Json::as_str) {
+                Some(
+                    "<i8>" | "<i16>" | "<i32>" | "<i64>" | "<isize>" | "<u8>" | "<u16>" | "<u32>"
+                    | "<u64>" | "<usize>",
+                ) => ValueKind::Int,
+                Some("<f32>" | "<f64>") => ValueKind::Float,
+                _ => ValueKind::Str,
+            },
+        };
+        let index = match json.get("index") {
+            Some(Json::Str(index)) => Some(Index::parse(index)?),
+            Some(Json::Num(index)) => {
+                if index.fract() != 0.0 || *index < 0.0 || *index > usize::MAX as f64 {
+                    return Err(err!(
+                        "invalid schema: index of option `{}` must be a non-negative integer, found {}",
+                        name,
+                        index
+                    ));
+                }
+                Some(Index::forward(*index as usize))
+            }
+            _ if styles.contains(&"Pos") => Some(Index::anywhere()),
+            _ => None,
+        };
+
+        Ok(Self {
+            name: name.to_owned(),
+            aliases: strs("aliases").into_iter().map(String::from).collect(),
+            kind,
+            index,
+            force: json
+                .get("force")
+                .and_then(Json::as_bool)
+                .unwrap_or_default(),
+            help: json
+                .get("help")
+                .and_then(Json::as_str)
+                .unwrap_or_default()
+                .to_owned(),
+            env: json.get("env").and_then(Json::as_str).map(String::from),
+        })
+    }
+}
+
+/// The command defined at runtime, such as loaded from a JSON or TOML schema. The options are
+/// created by the [`Creator`](crate::prelude::Creator) same as the derived command, and
+/// the values are returned in [`ParsedValues`].
+#[derive(Debug, Clone, Default)]
+pub struct DynCommand {
+    name: String,
+    about: String,
+    opts: Vec<DynOpt>,
+    subs: Vec<DynCommand>,
+}
+
+impl DynCommand {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_about(mut self, about: impl Into<String>) -> Self {
+        self.about = about.into();
+        self
+    }
+
+    pub fn with_opt(mut self, opt: DynOpt) -> Self {
+        self.opts.push(opt);
+        self
+    }
+
+    pub fn with_sub(mut self, sub: DynCommand) -> Self {
+        self.subs.push(sub);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn about(&self) -> &str {
+        &self.about
+    }
+
+    pub fn opts(&self) -> &[DynOpt] {
+        &self.opts
+    }
+
+    pub fn subs(&self) -> &[DynCommand] {
+        &self.subs
+    }
+
+    /// Read the command from the JSON object with the same layout as
+    /// [`Schema`](crate::schema::Schema), an option can set `kind` to one of
+    /// `bool`, `string`, `int`, `float` and `list`.
+    pub fn from_json(json: &Json) -> Result<Self, Error> {
+        let name = json
+            .get("name")
+            .and_then(Json::as_str)
+            .ok_or_else(|| err!("invalid schema: command must have a `name`"))?;
+        let list = |key: &str| json.get(key).and_then(Json::as_array).unwrap_or_default();
+        let mut ret = Self::new(name)
+            .with_about(json.get("about").and_then(Json::as_str).unwrap_or_default());
+
+        for opt in list("options") {
+            ret.opts.push(DynOpt::from_json(opt)?);
+        }
+        for sub in list("subcommands") {
+            ret.subs.push(Self::from_json(sub)?);
+        }
+        Ok(ret)
+    }
+
+    /// Parse the JSON text of schema, see [`from_json`](DynCommand::from_json). It is enabled
+    /// by the `serde_json` feature.
+    #[cfg(feature = "serde_json")]
+    pub fn from_schema(text: &str) -> Result<Self, Error> {
+        Self::from_json(&Json::parse(text)?)
+    }
+
+    /// Parse the TOML text of schema, the tables are read same as the JSON objects, such as
+    /// the options are written as `[[options]]`, see [`from_json`](DynCommand::from_json).
+    /// It is enabled by the `toml` feature.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self, Error> {
+        let value = text
+            .parse::<toml::Table>()
+            .map_err(|e| err!("invalid toml: {}", e))?;
+
+        Self::from_json(&Json::from(toml::Value::Table(value)))
+    }
+
+    /// Parse the arguments, the first argument is the program name.
+    pub fn parse(&self, args: Args) -> Result<ParsedValues, Error> {
+        let (cmd, handles) = self.build();
+
+        cmd.parse(args)?;
+        Ok(handles.collect())
+    }
+
+    /// Build the [`Command`] of the definition, the values are read by the handles.
+    fn build(&self) -> (Command, DynHandles) {
+        let mut cmd = Command::new(&self.name).with_about(&self.about);
+        let mut handles = DynHandles::default();
+
+        for opt in self.opts.iter() {
+            let handle = match opt.kind {
+                ValueKind::Bool => DynHandle::Bool(opt.add(&mut cmd)),
+                ValueKind::Str => DynHandle::Str(opt.add(&mut cmd)),
+                ValueKind::Int => DynHandle::Int(opt.add(&mut cmd)),
+                ValueKind::Float => DynHandle::Float(opt.add(&mut cmd)),
+                ValueKind::List => DynHandle::List(opt.add(&mut cmd)),
+            };
+
+            handles.opts.push((opt.name.clone(), handle));
+        }
+        for sub in self.subs.iter() {
+            let (sub_cmd, sub_handles) = sub.build();
+
+            handles
+                .subs
+                .push((sub.name.clone(), cmd.sub(sub_cmd), sub_handles));
+        }
+        (cmd, handles)
+    }
+}
+
+enum DynHandle {
+    Bool(Handle<bool>),
+
+    Str(Handle<String>),
+
+    Int(Handle<i64>),
+
+    Float(Handle<f64>),
+
+    List(Handle<Vec<String>>),
+}
+
+#[derive(Default)]
+struct DynHandles {
+    opts: Vec<(String, DynHandle)>,
+    subs: Vec<(String, Handle<bool>, DynHandles)>,
+}
+
+impl DynHandles {
+    fn collect(self) -> ParsedValues {
+        let mut ret = ParsedValues::default();
+
+        for (name, handle) in self.opts {
+            let value = match handle {
+                DynHandle::Bool(handle) => handle.take().map(Value::Bool),
+                DynHandle::Str(handle) => handle.take().map(Value::Str),
+                DynHandle::Int(handle) => handle.take().map(Value::Int),
+                DynHandle::Float(handle) => handle.take().map(Value::Float),
+                // the list is empty if the option is not present
+                DynHandle::List(handle) => handle.take().filter(|v| !v.is_empty()).map(Value::List),
+            };
+
+            if let Some(value) = value {
+                ret.values.insert(name, value);
+            }
+        }
+        for (name, matched, sub) in self.subs {
+            if matched.get() == Some(true) {
+                ret.sub = Some((name, Box::new(sub.collect())));
+            }
+        }
+        ret
+    }
+}
+
+// the commands of tests are loaded from the schema, which needs one of the features
+#[cfg(all(test, any(feature = "serde_json", feature = "toml")))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "serde_json")]
+    const SCHEMA: &str = r#"{
+        "name": "tool",
+        "options": [
+            { "name": "--debug", "aliases": ["-d"], "styles": ["Combined", "Boolean"] },
+            { "name": "--cfg", "action": "App" },
+            { "name": "--jobs", "value": "<u64>" },
+            { "name": "--ratio", "kind": "float" }
+        ]
+    }"#;
+
+    #[cfg(feature = "serde_json")]
+    const SUB_SCHEMA: &str = r#"{
+        "name": "git",
+        "subcommands": [
+            {
+                "name": "clone",
+                "options": [
+                    { "name": "repo", "index": "1", "force": true },
+                    { "name": "dir", "index": 2 }
+                ]
+            },
+            {
+                "name": "add",
+                "options": [{ "name": "files", "index": "1..", "kind": "list" }]
+            }
+        ]
+    }"#;
+
+    fn args(args: &[&str]) -> Args {
+        Args::from(args.to_vec())
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn parse_values() -> Result<(), Error> {
+        let git = DynCommand::from_schema(SCHEMA)?;
+        let kinds: Vec<_> = git.opts().iter().map(DynOpt::kind).collect();
+
+        assert_eq!(
+            kinds,
+            [
+                ValueKind::Bool,
+                ValueKind::List,
+                ValueKind::Int,
+                ValueKind::Float
+            ]
+        );
+
+        let values = git.parse(args(&[
+            "git",
+            "-d",
+            "--cfg",
+            "a=1",
+            "--cfg=b=2",
+            "--jobs",
+            "4",
+            "--ratio",
+            "0.5",
+        ]))?;
+
+        assert_eq!(values.get_bool("--debug"), Some(true));
+        assert_eq!(
+            values.get_list("--cfg"),
+            Some(&["a=1".to_owned(), "b=2".to_owned()][..])
+        );
+        assert_eq!(values.get_int("--jobs"), Some(4));
+        assert_eq!(values.get_float("--ratio"), Some(0.5));
+        assert_eq!(values.sub(), None);
+
+        let values = git.parse(args(&["tool"]))?;
+
+        assert_eq!(values.get_bool("--debug"), Some(false));
+        assert!(!values.contains("--cfg"));
+        assert!(!values.contains("--jobs"));
+        Ok(())
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn parse_sub() -> Result<(), Error> {
+        let git = DynCommand::from_schema(SUB_SCHEMA)?;
+        let values = git.parse(args(&["git", "clone", "url", "dir"]))?;
+        let (name, sub) = values.sub().unwrap();
+
+        assert_eq!(name, "clone");
+        assert_eq!(sub.get_str("repo"), Some("url"));
+        assert_eq!(sub.get_str("dir"), Some("dir"));
+
+        let values = git.parse(args(&["git", "add", "a", "b"]))?;
+        let (name, sub) = values.sub().unwrap();
+
+        assert_eq!(name, "add");
+        assert_eq!(
+            sub.get_list("files"),
+            Some(&["a".to_owned(), "b".to_owned()][..])
+        );
+
+        let error = git.parse(args(&["git", "clone"])).unwrap_err();
+
+        assert!(error.to_string().starts_with("git clone: "), "{}", error);
+        Ok(())
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn parse_toml_schema() -> Result<(), Error> {
+        let git = DynCommand::from_toml(
+            r#"
+            name = "git"
+
+            [[subcommands]]
+            name = "clone"
+            options = [{ name = "repo", index = "1", force = true }]
+
+            [[subcommands]]
+            name = "add"
+
+            [[subcommands.options]]
+            name = "files"
+            index = "1.."
+            kind = "list"
+            "#,
+        )?;
+
+        let names: Vec<_> = git.subs().iter().map(DynCommand::name).collect();
+
+        assert_eq!(names, ["clone", "add"]);
+
+        let values = git.parse(args(&["git", "add", "a", "b"]))?;
+        let (name, sub) = values.sub().unwrap();
+
+        assert_eq!(name, "add");
+        assert_eq!(
+            sub.get_list("files"),
+            Some(&["a".to_owned(), "b".to_owned()][..])
+        );
+        assert!(DynCommand::from_toml("name = ").is_err());
+        assert!(DynCommand::from_toml("[[options]]\nname = 1").is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn report_invalid_value() -> Result<(), Error> {
+        let git = DynCommand::from_schema(SCHEMA)?;
+        let error = git.parse(args(&["tool", "--jobs", "x"])).unwrap_err();
+
+        assert!(error.to_string().contains("`--jobs <i64>`"), "{}", error);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn reject_invalid_schema() {
+        for schema in [
+            r#"{ "options": [] }"#,
+            r#"{ "name": "git", "options": [{ "aliases": ["-d"] }] }"#,
+            r#"{ "name": "git", "options": [{ "name": "x", "kind": "map" }] }"#,
+            r#"{ "name": "git", "options": [{ "name": "x", "index": -1 }] }"#,
+            r#"{ "name": "git", "options": [{ "name": "x", "index": 1.5 }] }"#,
+        ] {
+            assert!(DynCommand::from_schema(schema).is_err(), "{}", schema);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::ffi::OsString;
+
+use aopt::opt::Opt as _;
+use aopt::opt::Style;
+use aopt::prelude::Args;
+use aopt::set::Ctor;
+use aopt::set::Set;
+
+use crate::opt::Opt;
+use crate::token::find_in;
+use crate::token::Token;
+use crate::token::Tokens;
+
+/// Find the options present in `args` before `--` or the sub command with their values, the
+/// combined short flags such as `-ab` are matched one by one.
+pub(crate) fn present_values<'a, 'b, S>(
+    set: &'a S,
+    args: &'b Args,
+) -> Vec<(&'a Opt, Option<&'b str>)>
+where
+    S: Set,
+    S::Ctor: Ctor<Opt = Opt>,
+{
+    let find = find_in(set);
+    let mut ret = vec![];
+    let mut tokens = Tokens::new(args.get(1..).unwrap_or_default());
+
+    while let Some((token, _)) = tokens.next_token(&find) {
+        match token {
+            Token::Opt { opt, values, .. } => {
+                ret.push((opt, values.first().and_then(|v| v.to_str())));
+            }
+            Token::Flags(opts) => ret.extend(opts.into_iter().map(|opt| (opt, None))),
+            Token::Cmd(_) | Token::Stop => break,
+            Token::Arg(_) => {}
+        }
+    }
+    ret
+}
+
+/// Insert the options not present in `args` from the environment variables, such as
+/// `--depth=3` for `GIT_CLONE_DEPTH=3`.
+///
+/// The boolean option is inserted if the variable is not empty, `0` or `false`.
+pub fn env_values<S>(set: &S, args: Args) -> Args
+where
+    S: Set,
+    S::Ctor: Ctor<Opt = Opt>,
+{
+    let present = present_values(set, &args);
+    let mut values: Vec<OsString> = vec![];
+
+    for opt in set.iter().filter(|opt| !opt.ignore_name()) {
+        let Some(value) = opt.env().and_then(std::env::var_os) else {
+            continue;
+        };
+
+        if present.iter().any(|(v, _)| v.uid() == opt.uid()) {
+            continue;
+        }
+        if opt.mat_style(Style::Boolean) {
+            if !matches!(value.to_str(), Some("" | "0" | "false")) {
+                values.push(opt.name().into());
+            }
+        } else {
+            let mut arg = OsString::from(format!("{}=", opt.name()));
+
+            arg.push(value);
+            values.push(arg);
+        }
+    }
+    if values.is_empty() {
+        return args;
+    }
+
+    let mut ret: Vec<OsString> = args.into();
+    let at = ret.len().min(1);
+
+    ret.splice(at..at, values);
+    Args::from(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use aopt::opt::ConfigValue;
+
+    use super::*;
+    use crate::prelude::OptSet;
+    use crate::test_util::insert_opt;
+    use crate::Error;
+
+    fn env_args(set: &OptSet, args: &[&str]) -> Vec<String> {
+        let args: Vec<OsString> = env_values(set, Args::from(args.to_vec())).into();
+
+        args.iter()
+            .map(|v| v.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn insert_values_not_present() -> Result<(), Error> {
+        let mut set = OptSet::default();
+
+        std::env::set_var("SOYA_TEST_ENV_DEBUG", "1");
+        std::env::set_var("SOYA_TEST_ENV_VERBOSE", "true");
+        std::env::set_var("SOYA_TEST_ENV_CFG", "a=1");
+        std::env::set_var("SOYA_TEST_ENV_DEPTH", "3");
+        insert_opt::<bool>(&mut set, "--debug", |cfg| {
+            cfg.add_alias("-d");
+        })?
+        .set_env("SOYA_TEST_ENV_DEBUG");
+        insert_opt::<bool>(&mut set, "--verbose", |cfg| {
+            cfg.add_alias("-v");
+        })?
+        .set_env("SOYA_TEST_ENV_VERBOSE");
+        insert_opt::<Option<String>>(&mut set, "--cfg", |cfg| {
+            cfg.add_alias("-c");
+        })?
+        .set_env("SOYA_TEST_ENV_CFG");
+        insert_opt::<Option<u64>>(&mut set, "--depth", |cfg| {
+            cfg.add_alias("-D");
+        })?
+        .set_env("SOYA_TEST_ENV_DEPTH");
+
+        assert_eq!(
+            env_args(&set, &["tool"]),
+            ["tool", "--debug", "--verbose", "--cfg=a=1", "--depth=3"]
+        );
+        // the value of `--cfg` is not the option `--depth`
+        assert_eq!(
+            env_args(&set, &["tool", "--cfg", "--depth"]),
+            [
+                "tool",
+                "--debug",
+                "--verbose",
+                "--depth=3",
+                "--cfg",
+                "--depth"
+            ]
+        );
+        // the combined flags are present
+        assert_eq!(
+            env_args(&set, &["tool", "-dv", "-D=1"]),
+            ["tool", "--cfg=a=1", "-dv", "-D=1"]
+        );
+        // the arguments after `--` are not options
+        assert_eq!(
+            env_args(&set, &["tool", "-c", "x", "--", "-d", "--depth=1"]),
+            [
+                "tool",
+                "--debug",
+                "--verbose",
+                "--depth=3",
+                "-c",
+                "x",
+                "--",
+                "-d",
+                "--depth=1"
+            ]
+        );
+        Ok(())
+    }
+}
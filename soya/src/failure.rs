@@ -0,0 +1,96 @@
+use std::cell::RefCell;
+use std::fmt::Display;
+
+use aopt::ctx::Ctx;
+
+use crate::err::fail;
+use crate::Error;
+
+/// Create an usage failure of command `cmd`, with a hint about the help option.
+pub fn usage_failure(cmd: &str, error: impl Display) -> Error {
+    fail!(
+        "{}: {}\n\nFor more information, try `{} --help`",
+        cmd,
+        error,
+        cmd
+    )
+}
+
+/// Collect the failures of parsing, report all of them in one error if `accumulate` enabled,
+/// otherwise only the first failure is reported.
+#[derive(Debug, Default)]
+pub struct Failures {
+    accumulate: bool,
+    failures: RefCell<Vec<Error>>,
+}
+
+impl Failures {
+    pub fn new(accumulate: bool) -> Self {
+        Self {
+            accumulate,
+            failures: RefCell::default(),
+        }
+    }
+
+    pub fn accumulate(&self) -> bool {
+        self.accumulate
+    }
+
+    pub fn push(&self, error: Error) {
+        self.failures.borrow_mut().push(error);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.failures.borrow().is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.failures.borrow().len()
+    }
+
+    /// Save the error of `ret` and return [`None`], so that it is reported with the
+    /// failures of parsing.
+    pub fn ok<T>(&self, ret: Result<T, Error>) -> Option<T> {
+        ret.map_err(|e| self.push(e)).ok()
+    }
+
+    /// Wrap the `handler`, the failure returned by it will be saved and the parsing continues.
+    ///
+    /// The failure is saved even if `accumulate` disabled, because the parser takes the failure
+    /// of handler as not matched and reports an unknown option instead, such as
+    /// `can not find option --jobs` for `--jobs x`. [`into_result`](Failures::into_result)
+    /// still reports only the first failure in that case.
+    pub fn collect<'a, S, O, H>(
+        &'a self,
+        mut handler: H,
+    ) -> impl FnMut(&mut S, &mut Ctx) -> Result<Option<O>, Error> + 'a
+    where
+        S: 'a,
+        O: 'a,
+        H: FnMut(&mut S, &mut Ctx) -> Result<Option<O>, Error> + 'a,
+    {
+        move |set, ctx| match handler(set, ctx) {
+            Err(e) if e.is_failure() => {
+                self.push(e);
+                Ok(None)
+            }
+            ret => ret,
+        }
+    }
+
+    /// Return the first failure, or a failure list all of them if `accumulate` enabled.
+    pub fn into_result(self) -> Result<(), Error> {
+        let mut failures = self.failures.into_inner();
+
+        match failures.len() {
+            0 => Ok(()),
+            1 => Err(failures.remove(0)),
+            _ if !self.accumulate => Err(failures.remove(0)),
+            count => {
+                let list: Vec<_> = failures.iter().map(|e| format!("  - {}", e)).collect();
+
+                Err(fail!("found {} errors:\n{}", count, list.join("\n")))
+            }
+        }
+    }
+}
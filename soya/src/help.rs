@@ -0,0 +1,150 @@
+use std::fmt::Write;
+
+use aopt::opt::Opt as _;
+use aopt::opt::Style;
+use aopt::set::Ctor;
+use aopt::set::Set;
+
+use crate::opt::Opt;
+use crate::relation::Group;
+
+fn write_section(out: &mut String, title: &str, opts: &[&Opt], width: usize) {
+    if !opts.is_empty() {
+        let _ = writeln!(out, "\n{}:", title);
+        for opt in opts {
+            let mut help = opt.help().to_owned();
+
+            if let (false, Some(value)) = (opt.mat_style(Style::Boolean), opt.default_value()) {
+                let _ = write!(help, " [default: {}]", value);
+            }
+            let line = format!(
+                "  {:<width$}  {}",
+                opt.hint(),
+                help.trim_start(),
+                width = width
+            );
+
+            let _ = writeln!(out, "{}", line.trim_end());
+        }
+    }
+}
+
+/// Generate the usage line of command `cmd` without the `Usage:` prefix, such as
+/// `git clone [OPTIONS] <repo> [dir]`.
+pub fn display_usage<S>(set: &S, cmd: &str) -> String
+where
+    S: Set,
+    S::Ctor: Ctor<Opt = Opt>,
+{
+    let opts: Vec<&Opt> = set
+        .iter()
+        .filter(|opt| !opt.mat_style(Style::Main) && !opt.hidden())
+        .collect();
+    let mut out = cmd.to_owned();
+
+    if opts
+        .iter()
+        .any(|opt| !opt.mat_style(Style::Cmd) && !opt.mat_style(Style::Pos))
+    {
+        out.push_str(" [OPTIONS]");
+    }
+    if opts.iter().any(|opt| opt.mat_style(Style::Cmd)) {
+        out.push_str(" <COMMAND>");
+    }
+    for pos in opts.iter().filter(|opt| opt.mat_style(Style::Pos)) {
+        let name = pos.name();
+
+        if pos.force() {
+            let _ = write!(out, " <{}>", name);
+        } else {
+            let _ = write!(out, " [{}]", name);
+        }
+    }
+    out
+}
+
+/// Generate the help message of command `cmd`, the options in `groups` are displayed together
+/// under the name of group.
+pub fn display_help<S>(set: &S, cmd: &str, groups: &[Group]) -> String
+where
+    S: Set,
+    S::Ctor: Ctor<Opt = Opt>,
+{
+    let opts: Vec<&Opt> = set
+        .iter()
+        .filter(|opt| !opt.mat_style(Style::Main) && !opt.hidden())
+        .collect();
+    let width = opts
+        .iter()
+        .map(|opt| opt.hint().len())
+        .max()
+        .unwrap_or_default();
+    let in_group = |opt: &Opt| groups.iter().any(|group| group.contains(opt.name()));
+    let cmds: Vec<_> = opts
+        .iter()
+        .filter(|opt| opt.mat_style(Style::Cmd))
+        .copied()
+        .collect();
+    let poss: Vec<_> = opts
+        .iter()
+        .filter(|opt| opt.mat_style(Style::Pos))
+        .copied()
+        .collect();
+    let options: Vec<_> = opts
+        .iter()
+        .filter(|opt| !opt.mat_style(Style::Cmd) && !opt.mat_style(Style::Pos))
+        .filter(|opt| !in_group(opt))
+        .copied()
+        .collect();
+    let mut out = format!("Usage: {}\n", display_usage(set, cmd));
+
+    write_section(&mut out, "Commands", &cmds, width);
+    write_section(&mut out, "Arguments", &poss, width);
+    write_section(&mut out, "Options", &options, width);
+    for group in groups {
+        let title = format!("{} ({})", group.name(), group.rule());
+        let opts: Vec<_> = group
+            .opts()
+            .iter()
+            .filter_map(|name| opts.iter().find(|opt| opt.name() == name).copied())
+            .collect();
+
+        write_section(&mut out, &title, &opts, width);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use aopt::opt::ConfigValue;
+
+    use super::*;
+    use crate::prelude::OptSet;
+    use crate::test_util::insert_opt;
+    use crate::value::ByteSize;
+    use crate::Error;
+
+    #[test]
+    fn display_default_value() -> Result<(), Error> {
+        let mut set = OptSet::default();
+
+        insert_opt::<ByteSize>(&mut set, "--size", |cfg| {
+            cfg.set_help("The size");
+        })?
+        .set_default_value(ByteSize::new(4096).to_string());
+        insert_opt::<bool>(&mut set, "--debug", |cfg| {
+            cfg.set_help("The debug");
+        })?
+        .set_default_value("false");
+        insert_opt::<Option<u64>>(&mut set, "--jobs", |cfg| {
+            cfg.set_help("The jobs");
+        })?;
+
+        let help = display_help(&set, "tool", &[]);
+
+        assert!(help.contains("The size [default: 4KiB]\n"), "{}", help);
+        assert!(help.contains("The debug\n"), "{}", help);
+        assert!(help.contains("The jobs\n"), "{}", help);
+        Ok(())
+    }
+}
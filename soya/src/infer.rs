@@ -1,54 +1,169 @@
 use std::any::TypeId;
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::ffi::OsStr;
 use std::ffi::OsString;
+use std::fmt::Display;
+use std::hash::Hash;
 use std::io::Stdin;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::net::SocketAddr;
+use std::num::NonZeroI128;
+use std::num::NonZeroI16;
+use std::num::NonZeroI32;
+use std::num::NonZeroI64;
+use std::num::NonZeroI8;
+use std::num::NonZeroIsize;
+use std::num::NonZeroU128;
+use std::num::NonZeroU16;
+use std::num::NonZeroU32;
+use std::num::NonZeroU64;
+use std::num::NonZeroU8;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::ops::RangeFrom;
+use std::ops::RangeInclusive;
+use std::ops::RangeTo;
+use std::ops::RangeToInclusive;
 use std::path::PathBuf;
-
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::arity::split_values;
+use crate::arity::VALUE_SEPARATOR;
+use crate::completion::ValueHint;
+use crate::err::fail;
+use crate::opt::gen_hint;
+use crate::opt::Opt;
+use crate::value::from_str;
+use crate::value::invalid_value;
 use crate::Error;
 
+use aopt::ctx::Ctx;
 use aopt::opt::Cmd;
 use aopt::opt::Main;
+use aopt::opt::Opt as _;
 use aopt::opt::Pos;
 use aopt::prelude::Action;
 use aopt::prelude::ConfigValue;
 use aopt::prelude::Index;
 use aopt::prelude::Style;
+use aopt::set::Set;
+use aopt::set::SetExt;
 use aopt::value::Stop;
 
+/// Infer the configuration of option from the type of value.
+///
+/// The wrapper types compose with each other, every layer only decides its own part:
+/// [`Pos`] and [`Main`] decide the style and index, [`Option`] and [`Result`] make the option
+/// optional, [`Vec`] and other collections append the values, and the inner type decides
+/// the rest such as the value type.
+///
+/// ```
+/// # use soya::aopt::prelude::{Action, ConfigValue, OptConfig, Style};
+/// # use soya::prelude::{Infer, Pos};
+/// # use soya::err::Error;
+/// #
+/// fn fill<T: Infer>() -> Result<OptConfig, Error> {
+///     let mut cfg = OptConfig::default().with_name("value");
+///
+///     T::infer_fill_info(&mut cfg)?;
+///     Ok(cfg)
+/// }
+///
+/// fn check<T: Infer>(force: bool, action: Action, style: Style) -> Result<(), Error> {
+///     let cfg = fill::<T>()?;
+///
+///     assert_eq!(cfg.force(), Some(force));
+///     assert_eq!(cfg.action(), Some(&action));
+///     assert_eq!(cfg.style().map(|v| v.contains(&style)), Some(true));
+///     Ok(())
+/// }
+///
+/// check::<u64>(true, Action::Set, Style::Argument)?;
+/// check::<bool>(false, Action::Set, Style::Boolean)?;
+/// check::<Option<u64>>(false, Action::Set, Style::Argument)?;
+/// check::<Option<bool>>(false, Action::Set, Style::Boolean)?;
+/// check::<Vec<u64>>(true, Action::App, Style::Argument)?;
+/// check::<Option<Vec<u64>>>(false, Action::App, Style::Argument)?;
+/// check::<Vec<Option<u64>>>(true, Action::App, Style::Argument)?;
+/// check::<Result<u64, Error>>(false, Action::Set, Style::Argument)?;
+/// check::<Result<Vec<u64>, Error>>(false, Action::App, Style::Argument)?;
+/// check::<Pos<String>>(true, Action::Set, Style::Pos)?;
+/// check::<Pos<Option<String>>>(false, Action::Set, Style::Pos)?;
+/// check::<Pos<Vec<String>>>(true, Action::App, Style::Pos)?;
+/// check::<Pos<Option<Vec<String>>>>(false, Action::App, Style::Pos)?;
+/// check::<Pos<Result<Vec<String>, Error>>>(false, Action::App, Style::Pos)?;
+///
+/// // the inner type decides the value type
+/// assert_eq!(fill::<Option<Vec<u64>>>()?.r#type(), Some(&std::any::TypeId::of::<u64>()));
+/// # Ok::<(), Error>(())
+/// ```
 pub trait Infer: 'static {
     type Val;
 
+    /// The configuration of option inferred from the type.
+    ///
+    /// The wrapper types transform the configuration of inner type. The default implementation
+    /// collects the deprecated `infer_*` methods, so the old implementations keep working.
+    #[allow(deprecated)]
+    fn infer_spec() -> OptSpec {
+        OptSpec::new::<Self>()
+            .with_action(Self::infer_act())
+            .with_force(Self::infer_force())
+            .with_ctor(Self::infer_ctor())
+            .with_index(Self::infer_index())
+            .with_style(Self::infer_style())
+            .with_ignore_name(Self::infer_ignore_name())
+            .with_ignore_alias(Self::infer_ignore_alias())
+            .with_ignore_index(Self::infer_ignore_index())
+            .with_type_id(Self::infer_type_id())
+    }
+
+    #[deprecated(note = "override `infer_spec` instead")]
     fn infer_act() -> Action {
         Action::Set
     }
 
+    #[deprecated(note = "override `infer_spec` instead")]
     fn infer_force() -> bool {
         true
     }
 
+    #[deprecated(note = "override `infer_spec` instead")]
     fn infer_ctor() -> String {
         aopt::set::ctor_default_name()
     }
 
+    #[deprecated(note = "override `infer_spec` instead")]
     fn infer_index() -> Option<Index> {
         None
     }
 
+    #[deprecated(note = "override `infer_spec` instead")]
     fn infer_style() -> Vec<Style> {
         vec![Style::Argument]
     }
 
+    #[deprecated(note = "override `infer_spec` instead")]
     fn infer_ignore_name() -> bool {
         false
     }
 
+    #[deprecated(note = "override `infer_spec` instead")]
     fn infer_ignore_alias() -> bool {
         false
     }
 
+    #[deprecated(note = "override `infer_spec` instead")]
     fn infer_ignore_index() -> bool {
         true
     }
 
+    #[deprecated(note = "override `infer_spec` instead")]
     fn infer_type_id() -> TypeId {
         std::any::TypeId::of::<Self>()
     }
@@ -66,6 +181,23 @@ pub trait Infer: 'static {
 
     fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error>;
 
+    /// Same as [`infer_map`](Infer::infer_map), the failure is named by the context `ctx`.
+    fn infer_map_with(ctx: &InferCtx, val: Result<Self::Val, Error>) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        Self::infer_map(val).map_err(|e| ctx.wrap(e))
+    }
+
+    /// Same as [`infer_mut`](Infer::infer_mut), the failure is named by the context `ctx`.
+    fn infer_mut_with(
+        &mut self,
+        ctx: &InferCtx,
+        val: Result<Self::Val, Error>,
+    ) -> Result<(), Error> {
+        self.infer_mut(val).map_err(|e| ctx.wrap(e))
+    }
+
     fn infer_tweak_info<C>(_cfg: &mut C) -> Result<(), Error>
     where
         Self: Sized,
@@ -80,40 +212,374 @@ pub trait Infer: 'static {
         Self: Sized,
         C: ConfigValue + Default,
     {
-        let act = Self::infer_act();
-        let style = Self::infer_style();
-        let index = Self::infer_index();
-        let ignore_name = Self::infer_ignore_name();
-        let ignore_alias = Self::infer_ignore_alias();
-        let ignore_index = Self::infer_ignore_index();
-        let force = Self::infer_force();
-        let ctor = Self::infer_ctor();
-        let type_id = Self::infer_type_id();
+        let OptSpec {
+            action,
+            force,
+            ctor,
+            index,
+            style,
+            ignore_name,
+            ignore_alias,
+            ignore_index,
+            type_id,
+            possible_values,
+            value_name,
+            ..
+        } = Self::infer_spec();
 
         Self::infer_tweak_info(cfg)?;
         (!cfg.has_ctor()).then(|| cfg.set_ctor(ctor));
         (!cfg.has_index()).then(|| index.map(|idx| cfg.set_index(idx)));
         (!cfg.has_type()).then(|| cfg.set_type_id(type_id));
-        (!cfg.has_action()).then(|| cfg.set_action(act));
+        (!cfg.has_action()).then(|| cfg.set_action(action));
         (!cfg.has_style()).then(|| cfg.set_style(style));
         (!cfg.has_force()).then(|| cfg.set_force(force));
-        (!cfg.has_action()).then(|| cfg.set_action(act));
         cfg.set_ignore_name(ignore_name);
         cfg.set_ignore_alias(ignore_alias);
         cfg.set_ignore_index(ignore_index);
+        if let Some(values) = possible_values {
+            let help = cfg.help().map(|v| format!("{} ", v)).unwrap_or_default();
+
+            cfg.set_help(format!("{}[possible values: {}]", help, values.join(", ")));
+        }
+        if let Some(value_name) = value_name {
+            if let (false, Some(name)) = (cfg.has_hint(), cfg.name()) {
+                let hint = gen_hint(None::<&str>, name, cfg.index(), cfg.alias());
+
+                cfg.set_hint(format!("{} {}", hint, value_name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fill the information of option which can not be passed by configuration.
+    fn infer_fill_opt(opt: &mut Opt) -> Result<(), Error>
+    where
+        Self: Sized,
+    {
+        let spec = Self::infer_spec();
+        let value_name = spec
+            .value_name
+            .unwrap_or_else(|| format!("<{}>", short_type_name::<Self::Val>()));
+
+        opt.set_value_name(value_name);
+        opt.set_arity(spec.arity);
+        opt.set_possible_values(spec.possible_values);
+        opt.set_value_hint(spec.value_hint);
+        if let Some(default_value) = spec.default_value {
+            opt.set_default_value(default_value);
+        }
         Ok(())
     }
 }
 
-impl Infer for bool {
-    type Val = bool;
+/// The configuration of option inferred from type, see [`Infer::infer_spec`].
+#[derive(Debug, Clone)]
+pub struct OptSpec {
+    action: Action,
+    force: bool,
+    ctor: String,
+    index: Option<Index>,
+    style: Vec<Style>,
+    ignore_name: bool,
+    ignore_alias: bool,
+    ignore_index: bool,
+    type_id: TypeId,
+    possible_values: Option<Vec<String>>,
+    value_name: Option<String>,
+    value_hint: ValueHint,
+    arity: Option<RangeInclusive<usize>>,
+    default_value: Option<String>,
+}
 
-    fn infer_force() -> bool {
-        false
+impl OptSpec {
+    /// The configuration of an option which takes one argument of type `T`.
+    pub fn new<T: ?Sized + 'static>() -> Self {
+        Self {
+            action: Action::Set,
+            force: true,
+            ctor: aopt::set::ctor_default_name(),
+            index: None,
+            style: vec![Style::Argument],
+            ignore_name: false,
+            ignore_alias: false,
+            ignore_index: true,
+            type_id: TypeId::of::<T>(),
+            possible_values: None,
+            value_name: None,
+            value_hint: ValueHint::Any,
+            arity: None,
+            default_value: None,
+        }
     }
 
-    fn infer_style() -> Vec<Style> {
-        vec![Style::Combined, Style::Boolean]
+    pub fn with_action(mut self, action: Action) -> Self {
+        self.action = action;
+        self
+    }
+
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    pub fn with_ctor(mut self, ctor: impl Into<String>) -> Self {
+        self.ctor = ctor.into();
+        self
+    }
+
+    pub fn with_index(mut self, index: Option<Index>) -> Self {
+        self.index = index;
+        self
+    }
+
+    pub fn with_style(mut self, style: Vec<Style>) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn with_ignore_name(mut self, ignore_name: bool) -> Self {
+        self.ignore_name = ignore_name;
+        self
+    }
+
+    pub fn with_ignore_alias(mut self, ignore_alias: bool) -> Self {
+        self.ignore_alias = ignore_alias;
+        self
+    }
+
+    pub fn with_ignore_index(mut self, ignore_index: bool) -> Self {
+        self.ignore_index = ignore_index;
+        self
+    }
+
+    pub fn with_type_id(mut self, type_id: TypeId) -> Self {
+        self.type_id = type_id;
+        self
+    }
+
+    /// The values accepted by the type, listed in the help of option.
+    pub fn with_possible_values(mut self, possible_values: Option<Vec<String>>) -> Self {
+        self.possible_values = possible_values;
+        self
+    }
+
+    /// The value part of hint appended after the option names, such as `<u64>,...`.
+    pub fn with_value_name(mut self, value_name: Option<String>) -> Self {
+        self.value_name = value_name;
+        self
+    }
+
+    /// The kind of value the shell should complete, such as file for [`PathBuf`].
+    pub fn with_value_hint(mut self, value_hint: ValueHint) -> Self {
+        self.value_hint = value_hint;
+        self
+    }
+
+    /// Number of arguments consumed by the option, `None` means the option takes one value as usual.
+    pub fn with_arity(mut self, arity: Option<RangeInclusive<usize>>) -> Self {
+        self.arity = arity;
+        self
+    }
+
+    /// The value used if the option is not present, such as `false` for [`bool`].
+    pub fn with_default_value(mut self, default_value: Option<String>) -> Self {
+        self.default_value = default_value;
+        self
+    }
+
+    pub fn action(&self) -> Action {
+        self.action
+    }
+
+    pub fn force(&self) -> bool {
+        self.force
+    }
+
+    pub fn ctor(&self) -> &str {
+        &self.ctor
+    }
+
+    pub fn index(&self) -> Option<&Index> {
+        self.index.as_ref()
+    }
+
+    pub fn style(&self) -> &[Style] {
+        &self.style
+    }
+
+    pub fn ignore_name(&self) -> bool {
+        self.ignore_name
+    }
+
+    pub fn ignore_alias(&self) -> bool {
+        self.ignore_alias
+    }
+
+    pub fn ignore_index(&self) -> bool {
+        self.ignore_index
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn possible_values(&self) -> Option<&[String]> {
+        self.possible_values.as_deref()
+    }
+
+    pub fn value_name(&self) -> Option<&str> {
+        self.value_name.as_deref()
+    }
+
+    pub fn value_hint(&self) -> ValueHint {
+        self.value_hint
+    }
+
+    pub fn arity(&self) -> Option<&RangeInclusive<usize>> {
+        self.arity.as_ref()
+    }
+
+    pub fn default_value(&self) -> Option<&str> {
+        self.default_value.as_deref()
+    }
+}
+
+/// The context of a conversion, it names the option, the raw argument and the position
+/// of the argument, see [`Infer::infer_map_with`].
+#[derive(Debug, Clone, Default)]
+pub struct InferCtx {
+    name: String,
+    alias: Option<String>,
+    raw: Option<OsString>,
+    index: usize,
+    value_name: String,
+}
+
+impl InferCtx {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Create the context of type `T` from the option matched by `ctx`.
+    pub fn from_ctx<T, S>(set: &S, ctx: &Ctx) -> Result<Self, Error>
+    where
+        T: Infer,
+        S: Set,
+    {
+        let opt = set.opt(ctx.uid()?)?;
+
+        Ok(Self::from_matched::<T>(ctx)?.with_name(opt.name()))
+    }
+
+    /// Create the context of type `T` from the argument matched by `ctx` only, the option
+    /// is named as it is used on the command line.
+    pub fn from_matched<T: Infer>(ctx: &Ctx) -> Result<Self, Error> {
+        let alias = ctx.name()?.filter(|v| !v.is_empty()).map(|v| v.to_string());
+        let value_name = T::infer_spec()
+            .value_name
+            .unwrap_or_else(|| format!("<{}>", short_type_name::<T::Val>()));
+
+        Ok(Self::new(alias.clone().unwrap_or_default())
+            .with_alias(alias)
+            .with_raw(ctx.arg()?.map(|v| v.to_os_string()))
+            .with_index(ctx.idx()?)
+            .with_value_name(value_name))
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// The name used on the command line, such as `-d` for option `--depth`.
+    pub fn with_alias(mut self, alias: Option<String>) -> Self {
+        self.alias = alias;
+        self
+    }
+
+    pub fn with_raw(mut self, raw: Option<OsString>) -> Self {
+        self.raw = raw;
+        self
+    }
+
+    pub fn with_index(mut self, index: usize) -> Self {
+        self.index = index;
+        self
+    }
+
+    pub fn with_value_name(mut self, value_name: impl Into<String>) -> Self {
+        self.value_name = value_name.into();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+
+    pub fn raw(&self) -> Option<&OsStr> {
+        self.raw.as_deref()
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn value_name(&self) -> &str {
+        &self.value_name
+    }
+
+    /// Display the option as `--depth <u64>`, or `<repo>` for the positional argument.
+    pub fn display(&self) -> String {
+        match self.alias() {
+            Some(alias) => format!("{} {}", alias, self.value_name),
+            None if self.name.is_empty() => self.value_name.clone(),
+            None => format!("<{}>", self.name),
+        }
+    }
+
+    /// Create the failure of the raw argument rejected by `reason`.
+    pub fn failure(&self, reason: impl Display) -> Error {
+        let raw = self
+            .raw()
+            .map(|v| v.to_string_lossy().replace(VALUE_SEPARATOR, " "))
+            .unwrap_or_default();
+        let reason = reason.to_string();
+
+        fail!(
+            "invalid value `{}` for `{}`: {}",
+            raw,
+            self.display(),
+            reason
+        )
+        .cause_by(fail!("{}", reason))
+    }
+
+    /// Name the option in the failure `error`, the cause of it is used as reason if any.
+    pub fn wrap(&self, error: Error) -> Error {
+        if error.is_failure() {
+            self.failure(error.caused_by().unwrap_or(&error))
+        } else {
+            error
+        }
+    }
+}
+
+impl Infer for bool {
+    type Val = bool;
+
+    fn infer_spec() -> OptSpec {
+        OptSpec::new::<Self>()
+            .with_force(false)
+            .with_style(vec![Style::Combined, Style::Boolean])
+            .with_default_value(Some("false".to_owned()))
     }
 
     fn infer_new() -> Option<Self>
@@ -139,16 +605,11 @@ impl Infer for bool {
 impl Infer for Cmd {
     type Val = bool;
 
-    fn infer_index() -> Option<Index> {
-        Some(Index::forward(1))
-    }
-
-    fn infer_style() -> Vec<Style> {
-        vec![Style::Cmd]
-    }
-
-    fn infer_ignore_index() -> bool {
-        false
+    fn infer_spec() -> OptSpec {
+        OptSpec::new::<Self>()
+            .with_index(Some(Index::forward(1)))
+            .with_style(vec![Style::Cmd])
+            .with_ignore_index(false)
     }
 
     fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
@@ -164,23 +625,17 @@ impl Infer for Cmd {
     }
 }
 
+/// The positional argument, the action and force are decided by `T`.
 impl<T: Infer + 'static> Infer for Pos<T> {
     type Val = <T as Infer>::Val;
 
-    fn infer_style() -> Vec<Style> {
-        vec![Style::Pos]
-    }
-
-    fn infer_ignore_name() -> bool {
-        true
-    }
-
-    fn infer_ignore_alias() -> bool {
-        true
-    }
-
-    fn infer_ignore_index() -> bool {
-        false
+    fn infer_spec() -> OptSpec {
+        <T as Infer>::infer_spec()
+            .with_style(vec![Style::Pos])
+            .with_ignore_name(true)
+            .with_ignore_alias(true)
+            .with_ignore_index(false)
+            .with_type_id(TypeId::of::<Self>())
     }
 
     fn infer_new() -> Option<Self>
@@ -200,33 +655,36 @@ impl<T: Infer + 'static> Infer for Pos<T> {
     fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
         Infer::infer_mut(&mut self.0, val)
     }
-}
 
-impl<T: Infer> Infer for Main<T> {
-    type Val = <T as Infer>::Val;
-
-    fn infer_act() -> Action {
-        Action::Null
-    }
-
-    fn infer_index() -> Option<Index> {
-        Some(Index::anywhere())
-    }
-
-    fn infer_style() -> Vec<Style> {
-        vec![Style::Main]
+    fn infer_map_with(ctx: &InferCtx, val: Result<Self::Val, Error>) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        Ok(Pos::new(<T as Infer>::infer_map_with(ctx, val)?))
     }
 
-    fn infer_ignore_name() -> bool {
-        true
+    fn infer_mut_with(
+        &mut self,
+        ctx: &InferCtx,
+        val: Result<Self::Val, Error>,
+    ) -> Result<(), Error> {
+        Infer::infer_mut_with(&mut self.0, ctx, val)
     }
+}
 
-    fn infer_ignore_alias() -> bool {
-        true
-    }
+impl<T: Infer> Infer for Main<T> {
+    type Val = <T as Infer>::Val;
 
-    fn infer_ignore_index() -> bool {
-        false
+    fn infer_spec() -> OptSpec {
+        <T as Infer>::infer_spec()
+            .with_action(Action::Null)
+            .with_force(true)
+            .with_index(Some(Index::anywhere()))
+            .with_style(vec![Style::Main])
+            .with_ignore_name(true)
+            .with_ignore_alias(true)
+            .with_ignore_index(false)
+            .with_type_id(TypeId::of::<Self>())
     }
 
     fn infer_new() -> Option<Self>
@@ -246,21 +704,30 @@ impl<T: Infer> Infer for Main<T> {
     fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
         Infer::infer_mut(&mut self.0, val)
     }
-}
 
-impl Infer for Stdin {
-    type Val = Stdin;
-
-    fn infer_act() -> Action {
-        Action::Set
+    fn infer_map_with(ctx: &InferCtx, val: Result<Self::Val, Error>) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        Ok(Main::new(<T as Infer>::infer_map_with(ctx, val)?))
     }
 
-    fn infer_style() -> Vec<Style> {
-        vec![Style::Boolean]
+    fn infer_mut_with(
+        &mut self,
+        ctx: &InferCtx,
+        val: Result<Self::Val, Error>,
+    ) -> Result<(), Error> {
+        Infer::infer_mut_with(&mut self.0, ctx, val)
     }
+}
 
-    fn infer_ignore_alias() -> bool {
-        true
+impl Infer for Stdin {
+    type Val = Stdin;
+
+    fn infer_spec() -> OptSpec {
+        OptSpec::new::<Self>()
+            .with_style(vec![Style::Boolean])
+            .with_ignore_alias(true)
     }
 
     fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
@@ -292,16 +759,10 @@ impl Infer for Stdin {
 impl Infer for Stop {
     type Val = Stop;
 
-    fn infer_act() -> Action {
-        Action::Set
-    }
-
-    fn infer_style() -> Vec<Style> {
-        vec![Style::Boolean]
-    }
-
-    fn infer_ignore_alias() -> bool {
-        true
+    fn infer_spec() -> OptSpec {
+        OptSpec::new::<Self>()
+            .with_style(vec![Style::Boolean])
+            .with_ignore_alias(true)
     }
 
     fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
@@ -333,40 +794,10 @@ impl Infer for Stop {
 impl<T: Infer> Infer for Option<T> {
     type Val = <T as Infer>::Val;
 
-    fn infer_act() -> Action {
-        <T as Infer>::infer_act()
-    }
-
-    fn infer_force() -> bool {
-        false
-    }
-
-    fn infer_ctor() -> String {
-        <T as Infer>::infer_ctor()
-    }
-
-    fn infer_index() -> Option<Index> {
-        <T as Infer>::infer_index()
-    }
-
-    fn infer_style() -> Vec<Style> {
-        <T as Infer>::infer_style()
-    }
-
-    fn infer_ignore_name() -> bool {
-        <T as Infer>::infer_ignore_name()
-    }
-
-    fn infer_ignore_alias() -> bool {
-        <T as Infer>::infer_ignore_alias()
-    }
-
-    fn infer_ignore_index() -> bool {
-        <T as Infer>::infer_ignore_index()
-    }
-
-    fn infer_type_id() -> TypeId {
-        <T as Infer>::infer_type_id()
+    fn infer_spec() -> OptSpec {
+        <T as Infer>::infer_spec()
+            .with_force(false)
+            .with_default_value(None)
     }
 
     fn infer_new() -> Option<Self>
@@ -392,61 +823,44 @@ impl<T: Infer> Infer for Option<T> {
         Ok(())
     }
 
-    fn infer_tweak_info<C>(cfg: &mut C) -> Result<(), Error>
+    fn infer_map_with(ctx: &InferCtx, val: Result<Self::Val, Error>) -> Result<Self, Error>
     where
         Self: Sized,
-
-        C: ConfigValue + Default,
     {
-        <T as Infer>::infer_tweak_info(cfg)
+        T::infer_map_with(ctx, val).map(|v| Some(v))
     }
 
-    fn infer_fill_info<C>(cfg: &mut C) -> Result<(), Error>
+    fn infer_mut_with(
+        &mut self,
+        ctx: &InferCtx,
+        val: Result<Self::Val, Error>,
+    ) -> Result<(), Error> {
+        if let Some(value) = self {
+            value.infer_mut_with(ctx, val)?;
+        } else {
+            *self = Self::infer_map_with(ctx, val)?;
+        }
+        Ok(())
+    }
+
+    fn infer_tweak_info<C>(cfg: &mut C) -> Result<(), Error>
     where
         Self: Sized,
+
         C: ConfigValue + Default,
     {
-        <T as Infer>::infer_fill_info(cfg)
+        <T as Infer>::infer_tweak_info(cfg)
     }
 }
 
 impl<T: Infer> Infer for Vec<T> {
     type Val = <T as Infer>::Val;
 
-    fn infer_act() -> Action {
-        Action::App
-    }
-
-    fn infer_force() -> bool {
-        true
-    }
-
-    fn infer_ctor() -> String {
-        <T as Infer>::infer_ctor()
-    }
-
-    fn infer_index() -> Option<Index> {
-        <T as Infer>::infer_index()
-    }
-
-    fn infer_style() -> Vec<Style> {
-        <T as Infer>::infer_style()
-    }
-
-    fn infer_ignore_name() -> bool {
-        <T as Infer>::infer_ignore_name()
-    }
-
-    fn infer_ignore_alias() -> bool {
-        <T as Infer>::infer_ignore_alias()
-    }
-
-    fn infer_ignore_index() -> bool {
-        <T as Infer>::infer_ignore_index()
-    }
-
-    fn infer_type_id() -> TypeId {
-        <T as Infer>::infer_type_id()
+    fn infer_spec() -> OptSpec {
+        <T as Infer>::infer_spec()
+            .with_action(Action::App)
+            .with_force(true)
+            .with_default_value(None)
     }
 
     fn infer_new() -> Option<Self>
@@ -468,64 +882,107 @@ impl<T: Infer> Infer for Vec<T> {
         Ok(())
     }
 
-    fn infer_tweak_info<C>(cfg: &mut C) -> Result<(), Error>
+    fn infer_map_with(ctx: &InferCtx, val: Result<Self::Val, Error>) -> Result<Self, Error>
     where
         Self: Sized,
-
-        C: ConfigValue + Default,
     {
-        <T as Infer>::infer_tweak_info(cfg)
+        Ok(vec![T::infer_map_with(ctx, val)?])
     }
 
-    fn infer_fill_info<C>(cfg: &mut C) -> Result<(), Error>
+    fn infer_mut_with(
+        &mut self,
+        ctx: &InferCtx,
+        val: Result<Self::Val, Error>,
+    ) -> Result<(), Error> {
+        self.push(T::infer_map_with(ctx, val)?);
+        Ok(())
+    }
+
+    fn infer_tweak_info<C>(cfg: &mut C) -> Result<(), Error>
     where
         Self: Sized,
+
         C: ConfigValue + Default,
     {
-        <T as Infer>::infer_fill_info(cfg)
+        <T as Infer>::infer_tweak_info(cfg)
     }
 }
 
-impl<T: Infer, E> Infer for Result<T, E>
-where
-    E: From<Error> + 'static,
-{
-    type Val = <T as Infer>::Val;
+/// Implement [`Infer`] for collection type same as [`Vec`], the value is added by `$add`.
+macro_rules! impl_collection_for {
+    ($coll:ident, $add:ident $(, $bound:path)*) => {
+        impl<T: Infer $(+ $bound)*> Infer for $coll<T> {
+            type Val = <T as Infer>::Val;
+
+            fn infer_spec() -> OptSpec {
+                <T as Infer>::infer_spec()
+                    .with_action(Action::App)
+                    .with_force(true)
+                    .with_default_value(None)
+            }
 
-    fn infer_act() -> Action {
-        <T as Infer>::infer_act()
-    }
+            fn infer_new() -> Option<Self>
+            where
+                Self: Sized,
+            {
+                Some($coll::new())
+            }
 
-    fn infer_force() -> bool {
-        false
-    }
+            fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+            where
+                Self: Sized,
+            {
+                let mut ret = $coll::new();
 
-    fn infer_ctor() -> String {
-        <T as Infer>::infer_ctor()
-    }
+                ret.$add(T::infer_map(val)?);
+                Ok(ret)
+            }
 
-    fn infer_index() -> Option<Index> {
-        <T as Infer>::infer_index()
-    }
+            fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+                self.$add(T::infer_map(val)?);
+                Ok(())
+            }
 
-    fn infer_style() -> Vec<Style> {
-        <T as Infer>::infer_style()
-    }
+            fn infer_map_with(ctx: &InferCtx, val: Result<Self::Val, Error>) -> Result<Self, Error>
+            where
+                Self: Sized,
+            {
+                let mut ret = $coll::new();
 
-    fn infer_ignore_name() -> bool {
-        <T as Infer>::infer_ignore_name()
-    }
+                ret.$add(T::infer_map_with(ctx, val)?);
+                Ok(ret)
+            }
 
-    fn infer_ignore_alias() -> bool {
-        <T as Infer>::infer_ignore_alias()
-    }
+            fn infer_mut_with(&mut self, ctx: &InferCtx, val: Result<Self::Val, Error>) -> Result<(), Error> {
+                self.$add(T::infer_map_with(ctx, val)?);
+                Ok(())
+            }
 
-    fn infer_ignore_index() -> bool {
-        <T as Infer>::infer_ignore_index()
-    }
+            fn infer_tweak_info<C>(cfg: &mut C) -> Result<(), Error>
+            where
+                Self: Sized,
+                C: ConfigValue + Default,
+            {
+                <T as Infer>::infer_tweak_info(cfg)
+            }
+        }
+    };
+}
 
-    fn infer_type_id() -> TypeId {
-        <T as Infer>::infer_type_id()
+impl_collection_for!(VecDeque, push_back);
+impl_collection_for!(HashSet, insert, Eq, Hash);
+impl_collection_for!(BTreeSet, insert, Ord);
+
+impl<T: Infer, E> Infer for Result<T, E>
+where
+    E: From<Error> + 'static,
+{
+    type Val = <T as Infer>::Val;
+
+    fn infer_spec() -> OptSpec {
+        <T as Infer>::infer_spec()
+            .with_force(false)
+            .with_default_value(None)
     }
 
     fn infer_new() -> Option<Self>
@@ -559,38 +1016,70 @@ where
         Ok(())
     }
 
-    fn infer_tweak_info<C>(cfg: &mut C) -> Result<(), Error>
+    fn infer_map_with(ctx: &InferCtx, val: Result<Self::Val, Error>) -> Result<Self, Error>
     where
         Self: Sized,
-
-        C: ConfigValue + Default,
     {
-        <T as Infer>::infer_tweak_info(cfg)
+        match val {
+            Ok(val) => Ok(T::infer_map_with(ctx, Ok(val)).map_err(Into::into)),
+            Err(e) => Ok(Err(ctx.wrap(e).into())),
+        }
     }
 
-    fn infer_fill_info<C>(cfg: &mut C) -> Result<(), Error>
+    fn infer_mut_with(
+        &mut self,
+        ctx: &InferCtx,
+        val: Result<Self::Val, Error>,
+    ) -> Result<(), Error> {
+        match val {
+            Ok(val) => {
+                if let Ok(value) = self.as_mut() {
+                    value.infer_mut_with(ctx, Ok(val))?;
+                }
+            }
+            Err(e) => {
+                *self = Err(ctx.wrap(e).into());
+            }
+        }
+        Ok(())
+    }
+
+    fn infer_tweak_info<C>(cfg: &mut C) -> Result<(), Error>
     where
         Self: Sized,
+
         C: ConfigValue + Default,
     {
-        <T as Infer>::infer_fill_info(cfg)
+        <T as Infer>::infer_tweak_info(cfg)
     }
 }
 
 #[macro_export]
 macro_rules! impl_value_for {
     ($type:ty) => {
+        $crate::impl_value_for!($type, $crate::completion::ValueHint::Any);
+    };
+    ($type:ty, $hint:expr) => {
         impl $crate::infer::Infer for $type {
             type Val = $type;
 
-            fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+            fn infer_spec() -> $crate::infer::OptSpec {
+                $crate::infer::OptSpec::new::<Self>().with_value_hint($hint)
+            }
+
+            fn infer_map(
+                val: Result<Self::Val, $crate::err::Error>,
+            ) -> Result<Self, $crate::err::Error>
             where
                 Self: Sized,
             {
                 val
             }
 
-            fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+            fn infer_mut(
+                &mut self,
+                val: Result<Self::Val, $crate::err::Error>,
+            ) -> Result<(), $crate::err::Error> {
                 *self = val?;
                 Ok(())
             }
@@ -614,9 +1103,313 @@ impl_value_for!(());
 impl_value_for!(usize);
 impl_value_for!(isize);
 impl_value_for!(String);
-impl_value_for!(PathBuf);
+impl_value_for!(PathBuf, ValueHint::File);
 impl_value_for!(OsString);
 
+/// Map the item to `T` by parsing [`FromStr`] value of `T::Val`.
+fn infer_item<T>(item: &str) -> Result<T, Error>
+where
+    T: Infer,
+    T::Val: FromStr,
+    <T::Val as FromStr>::Err: Display,
+{
+    T::infer_map(from_str::<T::Val>(item))
+}
+
+/// Implement [`Infer`] for tuple, the option consume one argument for each element, such as `--point 1 2`.
+macro_rules! impl_tuple_for {
+    ($len:literal, $($type:ident . $index:tt),+) => {
+        impl<$($type),+> Infer for ($($type,)+)
+        where
+            $(
+                $type: Infer,
+                $type::Val: FromStr,
+                <$type::Val as FromStr>::Err: Display,
+            )+
+        {
+            type Val = String;
+
+            fn infer_spec() -> OptSpec {
+                let value_name = [$(format!("<{}>", short_type_name::<$type>())),+].join(" ");
+
+                OptSpec::new::<Self>()
+                    .with_value_name(Some(value_name))
+                    .with_arity(Some($len..=$len))
+            }
+
+            fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+            where
+                Self: Sized,
+            {
+                let val = val?;
+                let values = split_values(&val, $len, $len)?;
+
+                Ok(($(infer_item::<$type>(values[$index])?,)+))
+            }
+
+            fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+                *self = Self::infer_map(val)?;
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_tuple_for!(2, A.0, B.1);
+impl_tuple_for!(3, A.0, B.1, C.2);
+impl_tuple_for!(4, A.0, B.1, C.2, D.3);
+impl_tuple_for!(5, A.0, B.1, C.2, D.3, E.4);
+impl_tuple_for!(6, A.0, B.1, C.2, D.3, E.4, F.5);
+
+/// The option consume `N` arguments, such as `--rgb 255 0 0`.
+impl<T, const N: usize> Infer for [T; N]
+where
+    T: Infer,
+    T::Val: FromStr,
+    <T::Val as FromStr>::Err: Display,
+{
+    type Val = String;
+
+    fn infer_spec() -> OptSpec {
+        let value_name = vec![format!("<{}>", short_type_name::<T>()); N].join(" ");
+
+        OptSpec::new::<Self>()
+            .with_value_name(Some(value_name))
+            .with_arity(Some(N..=N))
+    }
+
+    fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        let val = val?;
+        let values = split_values(&val, N, N)?
+            .into_iter()
+            .map(infer_item::<T>)
+            .collect::<Result<Vec<T>, Error>>()?;
+
+        values
+            .try_into()
+            .map_err(|_| fail!("expected {} values for array", N))
+    }
+
+    fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+        *self = Self::infer_map(val)?;
+        Ok(())
+    }
+}
+
+/// The parts of range such as `10..=20`.
+struct RangeParts<T> {
+    start: Option<T>,
+
+    end: Option<T>,
+
+    inclusive: bool,
+}
+
+/// Parse the range such as `10..20`, `10..=20`, `10..` and `..20`, check the start is not greater than the end.
+fn parse_range<T>(val: &str) -> Result<RangeParts<T>, Error>
+where
+    T: Infer + PartialOrd,
+    T::Val: FromStr,
+    <T::Val as FromStr>::Err: Display,
+{
+    let (start, end) = val
+        .split_once("..")
+        .ok_or_else(|| invalid_value(val, "expected a range such as `10..20`"))?;
+    let (end, inclusive) = match end.strip_prefix('=') {
+        Some(end) => (end, true),
+        None => (end, false),
+    };
+    let start = (!start.is_empty())
+        .then(|| infer_item::<T>(start))
+        .transpose()?;
+    let end = (!end.is_empty())
+        .then(|| infer_item::<T>(end))
+        .transpose()?;
+
+    if inclusive && end.is_none() {
+        return Err(invalid_value(val, "missing end of range"));
+    }
+    if let (Some(start), Some(end)) = (&start, &end) {
+        if start > end {
+            return Err(invalid_value(
+                val,
+                "the start of range is greater than the end",
+            ));
+        }
+    }
+    Ok(RangeParts {
+        start,
+        end,
+        inclusive,
+    })
+}
+
+/// Implement [`Infer`] for range type, `$shape` is the example of the accepted value.
+macro_rules! impl_range_for {
+    ($range:ident, $shape:literal, |$parts:ident| $convert:expr) => {
+        impl<T> Infer for $range<T>
+        where
+            T: Infer + PartialOrd,
+            T::Val: FromStr,
+            <T::Val as FromStr>::Err: Display,
+        {
+            type Val = String;
+
+            fn infer_spec() -> OptSpec {
+                let value_name = $shape.replace("T", &format!("<{}>", short_type_name::<T>()));
+
+                OptSpec::new::<Self>().with_value_name(Some(value_name))
+            }
+
+            fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+            where
+                Self: Sized,
+            {
+                let val = val?;
+                let $parts = parse_range::<T>(&val)?;
+
+                $convert.ok_or_else(|| {
+                    let shape = $shape.replace("T", &short_type_name::<T>());
+
+                    invalid_value(&val, format!("expected a range such as `{}`", shape))
+                })
+            }
+
+            fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+                *self = Self::infer_map(val)?;
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_range_for!(Range, "T..T", |parts| match parts {
+    RangeParts {
+        start: Some(start),
+        end: Some(end),
+        inclusive: false,
+    } => Some(start..end),
+    _ => None,
+});
+impl_range_for!(RangeInclusive, "T..=T", |parts| match parts {
+    RangeParts {
+        start: Some(start),
+        end: Some(end),
+        inclusive: true,
+    } => Some(start..=end),
+    _ => None,
+});
+impl_range_for!(RangeFrom, "T..", |parts| match parts {
+    RangeParts {
+        start: Some(start),
+        end: None,
+        ..
+    } => Some(start..),
+    _ => None,
+});
+impl_range_for!(RangeTo, "..T", |parts| match parts {
+    RangeParts {
+        start: None,
+        end: Some(end),
+        inclusive: false,
+    } => Some(..end),
+    _ => None,
+});
+impl_range_for!(RangeToInclusive, "..=T", |parts| match parts {
+    RangeParts {
+        start: None,
+        end: Some(end),
+        inclusive: true,
+    } => Some(..=end),
+    _ => None,
+});
+
+/// Get the name of type without module path, such as `String` for `alloc::string::String`.
+pub fn short_type_name<T: ?Sized>() -> String {
+    let name = std::any::type_name::<T>();
+    let mut ret = String::with_capacity(name.len());
+    let mut segment = String::new();
+
+    for ch in name.chars() {
+        if ch.is_alphanumeric() || ch == '_' || ch == ':' {
+            segment.push(ch);
+        } else {
+            ret.push_str(segment.rsplit("::").next().unwrap_or_default());
+            segment.clear();
+            ret.push(ch);
+        }
+    }
+    ret.push_str(segment.rsplit("::").next().unwrap_or_default());
+    ret
+}
+
+/// Implement [`Infer`] for the type by parsing [`String`] with [`FromStr`](std::str::FromStr).
+macro_rules! impl_value_from_str {
+    ($type:ty) => {
+        impl Infer for $type {
+            type Val = String;
+
+            fn infer_spec() -> OptSpec {
+                OptSpec::new::<Self>().with_value_name(Some(format!("<{}>", stringify!($type))))
+            }
+
+            fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+            where
+                Self: Sized,
+            {
+                crate::value::from_str(&val?)
+            }
+
+            fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+                *self = Self::infer_map(val)?;
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_value_from_str!(char);
+impl_value_from_str!(IpAddr);
+impl_value_from_str!(Ipv4Addr);
+impl_value_from_str!(Ipv6Addr);
+impl_value_from_str!(SocketAddr);
+impl_value_from_str!(NonZeroI8);
+impl_value_from_str!(NonZeroI16);
+impl_value_from_str!(NonZeroI32);
+impl_value_from_str!(NonZeroI64);
+impl_value_from_str!(NonZeroI128);
+impl_value_from_str!(NonZeroIsize);
+impl_value_from_str!(NonZeroU8);
+impl_value_from_str!(NonZeroU16);
+impl_value_from_str!(NonZeroU32);
+impl_value_from_str!(NonZeroU64);
+impl_value_from_str!(NonZeroU128);
+impl_value_from_str!(NonZeroUsize);
+
+/// Accept human friendly duration such as `30s`, `500ms` or `2m30s`.
+impl Infer for Duration {
+    type Val = String;
+
+    fn infer_spec() -> OptSpec {
+        OptSpec::new::<Self>().with_value_name(Some("<Duration>".to_owned()))
+    }
+
+    fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        crate::value::parse_duration(&val?)
+    }
+
+    fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+        *self = Self::infer_map(val)?;
+        Ok(())
+    }
+}
+
 // impl<T, E> Infer for Result<T, E>
 // where
 //     E: From<Error>,
@@ -655,3 +1448,85 @@ impl_value_for!(OsString);
 //         Ok(())
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map<T: Infer<Val = String>>(val: &str) -> Result<T, Error> {
+        T::infer_map(Ok(val.to_owned()))
+    }
+
+    struct Level(u8);
+
+    #[allow(deprecated)]
+    impl Infer for Level {
+        type Val = u8;
+
+        fn infer_force() -> bool {
+            false
+        }
+
+        fn infer_style() -> Vec<Style> {
+            vec![Style::Argument, Style::Combined]
+        }
+
+        fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error> {
+            val.map(Level)
+        }
+
+        fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+            self.0 = val?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn spec_of_deprecated_methods() -> Result<(), Error> {
+        let spec = Level::infer_spec();
+        let mut cfg = aopt::prelude::OptConfig::default().with_name("--level");
+
+        assert!(!spec.force());
+        assert_eq!(spec.style(), [Style::Argument, Style::Combined]);
+        Level::infer_fill_info(&mut cfg)?;
+        assert_eq!(cfg.force(), Some(false));
+        assert_eq!(Level::infer_map(Ok(3))?.0, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn value_name_of_str_values() {
+        assert_eq!(IpAddr::infer_spec().value_name(), Some("<IpAddr>"));
+        assert_eq!(NonZeroU32::infer_spec().value_name(), Some("<NonZeroU32>"));
+        assert_eq!(Duration::infer_spec().value_name(), Some("<Duration>"));
+    }
+
+    #[test]
+    fn parse_range_shapes() -> Result<(), Error> {
+        assert_eq!(map::<Range<u64>>("10..20")?, 10..20);
+        assert_eq!(map::<RangeInclusive<i64>>("-5..=5")?, -5..=5);
+        assert_eq!(map::<RangeFrom<u64>>("10..")?, 10..);
+        assert_eq!(map::<RangeTo<u64>>("..20")?, ..20);
+        assert_eq!(map::<RangeToInclusive<u64>>("..=20")?, ..=20);
+        assert_eq!(map::<Range<f64>>("0.5..1.5")?, 0.5..1.5);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_range_invalid() {
+        let failure = |val: &str| map::<Range<u64>>(val).unwrap_err().to_string();
+
+        assert!(failure("10").contains("expected a range such as `10..20`"));
+        assert!(failure("20..10").contains("the start of range is greater than the end"));
+        assert!(failure("10..=20").contains("expected a range such as `u64..u64`"));
+        assert!(failure("10..x").contains("invalid value `x`"));
+        assert!(map::<RangeInclusive<u64>>("10..=")
+            .unwrap_err()
+            .to_string()
+            .contains("missing end of range"));
+        assert!(map::<RangeFrom<u64>>("..20")
+            .unwrap_err()
+            .to_string()
+            .contains("expected a range such as `u64..`"));
+    }
+}
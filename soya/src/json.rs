@@ -0,0 +1,273 @@
+use std::fmt::Display;
+use std::fmt::Write;
+#[cfg(feature = "serde_json")]
+use std::str::FromStr;
+
+#[cfg(feature = "serde_json")]
+use crate::err::err;
+#[cfg(feature = "serde_json")]
+use crate::Error;
+
+/// The JSON value used by the [`Schema`](crate::schema::Schema) of command, the members of
+/// object keep the order of insertion.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Json {
+    #[default]
+    Null,
+
+    Bool(bool),
+
+    Num(f64),
+
+    Str(String),
+
+    Array(Vec<Json>),
+
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn str(val: impl Into<String>) -> Self {
+        Self::Str(val.into())
+    }
+
+    /// Map the [`None`] to [`Null`](Json::Null).
+    pub fn opt_str(val: Option<impl Into<String>>) -> Self {
+        val.map(Self::str).unwrap_or_default()
+    }
+
+    pub fn strs<T: Into<String>>(vals: impl IntoIterator<Item = T>) -> Self {
+        Self::Array(vals.into_iter().map(Self::str).collect())
+    }
+
+    /// Get the member of object.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Self::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    pub fn as_num(&self) -> Option<f64> {
+        match self {
+            Self::Num(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Self::Array(vals) => Some(vals),
+            _ => None,
+        }
+    }
+
+    /// Parse the JSON text by [`serde_json`], it is enabled by the `serde_json` feature.
+    #[cfg(feature = "serde_json")]
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        serde_json::from_str::<serde_json::Value>(text)
+            .map(Self::from)
+            .map_err(|e| err!("invalid json: {}", e))
+    }
+
+    /// Render the value with two spaces indent.
+    pub fn pretty(&self) -> String {
+        let mut out = String::new();
+
+        self.write(&mut out, Some(0));
+        out
+    }
+
+    fn write(&self, out: &mut String, indent: Option<usize>) {
+        let newline = |out: &mut String, level: usize| {
+            if indent.is_some() {
+                out.push('\n');
+                out.push_str(&"  ".repeat(level));
+            }
+        };
+        let level = indent.unwrap_or_default();
+        let next = indent.map(|v| v + 1);
+
+        match self {
+            Self::Null => out.push_str("null"),
+            Self::Bool(val) => {
+                let _ = write!(out, "{}", val);
+            }
+            Self::Num(val) => {
+                let _ = write!(out, "{}", val);
+            }
+            Self::Str(val) => write_str(out, val),
+            Self::Array(vals) if vals.is_empty() => out.push_str("[]"),
+            Self::Array(vals) => {
+                out.push('[');
+                for (i, val) in vals.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    newline(out, level + 1);
+                    val.write(out, next);
+                }
+                newline(out, level);
+                out.push(']');
+            }
+            Self::Object(members) if members.is_empty() => out.push_str("{}"),
+            Self::Object(members) => {
+                out.push('{');
+                for (i, (key, val)) in members.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    newline(out, level + 1);
+                    write_str(out, key);
+                    out.push(':');
+                    if indent.is_some() {
+                        out.push(' ');
+                    }
+                    val.write(out, next);
+                }
+                newline(out, level);
+                out.push('}');
+            }
+        }
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut out = String::new();
+
+        self.write(&mut out, None);
+        f.write_str(&out)
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl FromStr for Json {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl From<serde_json::Value> for Json {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => Self::Null,
+            serde_json::Value::Bool(val) => Self::Bool(val),
+            serde_json::Value::Number(val) => Self::Num(val.as_f64().unwrap_or(f64::NAN)),
+            serde_json::Value::String(val) => Self::Str(val),
+            serde_json::Value::Array(vals) => {
+                Self::Array(vals.into_iter().map(Self::from).collect())
+            }
+            serde_json::Value::Object(members) => Self::Object(
+                members
+                    .into_iter()
+                    .map(|(key, val)| (key, Self::from(val)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+fn write_str(out: &mut String, val: &str) {
+    out.push('"');
+    for ch in val.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", ch as u32);
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(feature = "toml")]
+impl From<toml::Value> for Json {
+    fn from(value: toml::Value) -> Self {
+        match value {
+            toml::Value::String(val) => Self::Str(val),
+            toml::Value::Integer(val) => Self::Num(val as f64),
+            toml::Value::Float(val) => Self::Num(val),
+            toml::Value::Boolean(val) => Self::Bool(val),
+            toml::Value::Datetime(val) => Self::Str(val.to_string()),
+            toml::Value::Array(vals) => Self::Array(vals.into_iter().map(Self::from).collect()),
+            toml::Value::Table(members) => Self::Object(
+                members
+                    .into_iter()
+                    .map(|(key, val)| (key, Self::from(val)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Json {
+        Json::Object(vec![
+            ("name".to_owned(), Json::str("git")),
+            ("debug".to_owned(), Json::Bool(true)),
+            ("depth".to_owned(), Json::Num(1.5)),
+            ("help".to_owned(), Json::str("a \"b\"\n\\c\u{1}")),
+            ("aliases".to_owned(), Json::strs(["-d", "--dbg"])),
+            ("env".to_owned(), Json::Null),
+            ("empty".to_owned(), Json::Array(vec![])),
+            ("subcommands".to_owned(), Json::Object(vec![])),
+        ])
+    }
+
+    #[test]
+    fn write_json() {
+        assert_eq!(
+            schema().to_string(),
+            r#"{"name":"git","debug":true,"depth":1.5,"help":"a \"b\"\n\\c\u0001","aliases":["-d","--dbg"],"env":null,"empty":[],"subcommands":{}}"#
+        );
+        assert_eq!(Json::strs(["a"]).pretty(), "[\n  \"a\"\n]");
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let json = schema();
+
+        assert_eq!(Json::parse(&json.to_string())?, json);
+        assert_eq!(Json::parse(&json.pretty())?, json);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn parse_invalid() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+        for text in ["", "[1,", "[1 2]", "{\"a\" 1}", "tru", "1 2", r#""\ud83d""#] {
+            assert!(Json::parse(text).is_err(), "{}", text);
+        }
+        // the nesting is limited by serde_json instead of overflowing the stack
+        assert!(Json::parse(&nested(100_000)).is_err());
+    }
+}
@@ -1,5 +1,23 @@
+pub mod arity;
+pub mod builder;
+pub mod completion;
+pub mod describe;
+pub mod dynamic;
+pub mod env;
+pub mod failure;
+pub mod help;
 pub mod infer;
+pub mod json;
+pub mod man;
 pub mod opt;
+pub mod reference;
+pub mod relation;
+pub mod schema;
+pub mod shell;
+pub mod to_args;
+mod token;
+pub mod validate;
+pub mod value;
 
 pub use aopt;
 
@@ -35,10 +53,45 @@ pub mod prelude {
     pub use aopt::prelude::Pos;
     pub use aopt::prelude::SetCfg;
     pub use aopt::set::ctor_default_name;
+    pub use aopt::value::Stop;
 
+    pub use crate::arity::group_values;
+    pub use crate::builder::Command;
+    pub use crate::builder::Handle;
+    pub use crate::completion::Completion;
+    pub use crate::completion::Shell;
+    pub use crate::completion::ValueHint;
+    pub use crate::describe::CmdDesc;
+    pub use crate::describe::OptDesc;
+    pub use crate::dynamic::DynCommand;
+    pub use crate::dynamic::DynOpt;
+    pub use crate::dynamic::ParsedValues;
+    pub use crate::dynamic::ValueKind;
+    pub use crate::env::env_values;
+    pub use crate::failure::Failures;
     pub use crate::fetch_or_update;
     pub use crate::fetch_or_update_handler;
+    pub use crate::help::display_help;
+    pub use crate::help::display_usage;
     pub use crate::infer::Infer;
+    pub use crate::infer::InferCtx;
+    pub use crate::infer::OptSpec;
+    pub use crate::json::Json;
+    pub use crate::man::Manual;
+    pub use crate::reference::Reference;
+    pub use crate::relation::check_relations;
+    pub use crate::relation::relation_failures;
+    pub use crate::relation::Group;
+    pub use crate::schema::Schema;
+    pub use crate::to_args::ToArgs;
+    pub use crate::to_args::ToRaw;
+    pub use crate::validate::Validator;
+    pub use crate::value::ByteSize;
+    pub use crate::value::Delimited;
+    pub use crate::value::FromStrValue;
+    pub use crate::value::Multi;
+    pub use crate::value::Radix;
+    pub use crate::value::Unique;
     pub use crate::ParserImpl;
 }
 
@@ -52,12 +105,32 @@ pub mod _macro {
     #[macro_export]
     macro_rules! fetch_or_update {
         ($ctx:ident, $id:ident, $type:ty) => {
-            let val = $ctx.value::<<$type as $crate::infer::Infer>::Val>();
+            let infer_ctx = $crate::infer::InferCtx::from_matched::<$type>($ctx)?;
+            let val = $ctx
+                .value::<<$type as $crate::infer::Infer>::Val>()
+                .map_err(|e| infer_ctx.wrap(e));
 
             if let Some(value) = $id.as_mut() {
-                <$type as $crate::infer::Infer>::infer_mut(value, val)?;
+                <$type as $crate::infer::Infer>::infer_mut_with(value, &infer_ctx, val)?;
             } else {
-                $id = Some(<$type as $crate::infer::Infer>::infer_map(val)?);
+                $id = Some(<$type as $crate::infer::Infer>::infer_map_with(
+                    &infer_ctx, val,
+                )?);
+            }
+        };
+        ($set:ident, $ctx:ident, $id:ident, $type:ty, $validator:expr) => {
+            let infer_ctx = $crate::infer::InferCtx::from_ctx::<$type, _>($set, $ctx)?;
+            let val = $ctx
+                .value::<<$type as $crate::infer::Infer>::Val>()
+                .map_err(|e| infer_ctx.wrap(e));
+            let val = $crate::validate::check(&infer_ctx, val, &$validator);
+
+            if let Some(value) = $id.as_mut() {
+                <$type as $crate::infer::Infer>::infer_mut_with(value, &infer_ctx, val)?;
+            } else {
+                $id = Some(<$type as $crate::infer::Infer>::infer_map_with(
+                    &infer_ctx, val,
+                )?);
             }
         };
     }
@@ -66,12 +139,35 @@ pub mod _macro {
     macro_rules! fetch_or_update_handler {
         ($id:ident, $type:ty) => {
             |set, ctx| {
-                let val = ctx.value::<<$type as $crate::infer::Infer>::Val>();
+                let infer_ctx = $crate::infer::InferCtx::from_ctx::<$type, _>(set, ctx)?;
+                let val = ctx
+                    .value::<<$type as $crate::infer::Infer>::Val>()
+                    .map_err(|e| infer_ctx.wrap(e));
 
                 if let Some(value) = $id.as_mut() {
-                    <$type as $crate::infer::Infer>::infer_mut(value, val)?;
+                    <$type as $crate::infer::Infer>::infer_mut_with(value, &infer_ctx, val)?;
                 } else {
-                    $id = Some(<$type as $crate::infer::Infer>::infer_map(val)?);
+                    $id = Some(<$type as $crate::infer::Infer>::infer_map_with(
+                        &infer_ctx, val,
+                    )?);
+                }
+                Ok(Some(()))
+            }
+        };
+        ($id:ident, $type:ty, $validator:expr) => {
+            |set, ctx| {
+                let infer_ctx = $crate::infer::InferCtx::from_ctx::<$type, _>(set, ctx)?;
+                let val = ctx
+                    .value::<<$type as $crate::infer::Infer>::Val>()
+                    .map_err(|e| infer_ctx.wrap(e));
+                let val = $crate::validate::check(&infer_ctx, val, &$validator);
+
+                if let Some(value) = $id.as_mut() {
+                    <$type as $crate::infer::Infer>::infer_mut_with(value, &infer_ctx, val)?;
+                } else {
+                    $id = Some(<$type as $crate::infer::Infer>::infer_map_with(
+                        &infer_ctx, val,
+                    )?);
                 }
                 Ok(Some(()))
             }
@@ -80,7 +176,13 @@ pub mod _macro {
 }
 
 use crate::err::Error;
-use aopt::{args::Args, parser::Policy, set::Set};
+use aopt::{args::Args, parser::Policy, set::Ctor, set::Set};
+use completion::Completion;
+use completion::Shell;
+use describe::CmdDesc;
+use man::Manual;
+use reference::Reference;
+use schema::Schema;
 
 pub trait ParserImpl<'inv> {
     type Error: Into<Error>;
@@ -109,14 +211,211 @@ pub trait ParserImpl<'inv> {
 
     fn apply_settings(policy: &mut Self::Policy<'inv>) -> Result<(), Self::Error>;
 
+    /// Report all the failures of parsing in one error instead of stopping at the first.
+    fn collect_failures() -> bool {
+        false
+    }
+
+    /// Check the rules span the fields, it is called by [`parse`](ParserImpl::parse)
+    /// after the value constructed.
+    fn validate(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// The description of command, such as the document of struct.
+    fn about() -> &'static str {
+        ""
+    }
+
+    /// Describe the command `name` and its options, it is rendered by [`manual`](ParserImpl::manual),
+    /// [`reference`](ParserImpl::reference), [`schema`](ParserImpl::schema) and
+    /// [`completion`](ParserImpl::completion). Override it to add the description of sub commands,
+    /// such as `Self::describe_opts(name)?.with_sub(Clone::describe("clone")?)`.
+    fn describe(name: &str) -> Result<CmdDesc, Self::Error>
+    where
+        Self: 'inv,
+        <Self::Parser<'inv> as Set>::Ctor: Ctor<Opt = opt::Opt>,
+    {
+        Self::describe_opts(name)
+    }
+
+    /// Describe the command `name` without the sub commands, see [`describe`](ParserImpl::describe).
+    fn describe_opts(name: &str) -> Result<CmdDesc, Self::Error>
+    where
+        Self: 'inv,
+        <Self::Parser<'inv> as Set>::Ctor: Ctor<Opt = opt::Opt>,
+    {
+        let parser = Self::into_parser()?;
+
+        Ok(CmdDesc::new(name, &parser).with_about(Self::about()))
+    }
+
+    /// The man pages of the command tree.
+    fn manual(name: &str) -> Result<Manual, Self::Error>
+    where
+        Self: 'inv,
+        <Self::Parser<'inv> as Set>::Ctor: Ctor<Opt = opt::Opt>,
+    {
+        Ok(Manual::new(Self::describe(name)?))
+    }
+
+    /// The Markdown reference of the command tree.
+    fn reference(name: &str) -> Result<Reference, Self::Error>
+    where
+        Self: 'inv,
+        <Self::Parser<'inv> as Set>::Ctor: Ctor<Opt = opt::Opt>,
+    {
+        Ok(Reference::new(Self::describe(name)?))
+    }
+
+    /// The JSON description of the command tree.
+    fn schema(name: &str) -> Result<Schema, Self::Error>
+    where
+        Self: 'inv,
+        <Self::Parser<'inv> as Set>::Ctor: Ctor<Opt = opt::Opt>,
+    {
+        Ok(Schema::new(Self::describe(name)?))
+    }
+
+    /// The completion of the command tree, override it to set the callbacks of options.
+    fn completion(name: &str) -> Result<Completion, Self::Error>
+    where
+        Self: 'inv,
+        <Self::Parser<'inv> as Set>::Ctor: Ctor<Opt = opt::Opt>,
+    {
+        Ok(Completion::new(&Self::describe(name)?))
+    }
+
+    /// Print the candidates and return `true` if the program is called by the shell for
+    /// completion, see [`complete_env`](completion::complete_env).
+    fn complete_env(name: &str) -> Result<bool, Self::Error>
+    where
+        Self: 'inv,
+        <Self::Parser<'inv> as Set>::Ctor: Ctor<Opt = opt::Opt>,
+    {
+        if std::env::var_os(completion::COMPLETE_ENV).is_some() {
+            Ok(completion::complete_env(&Self::completion(name)?))
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Print the completion script or the schema and return `true` if the hidden flag
+    /// [`GENERATE_COMPLETION`](completion::GENERATE_COMPLETION) with the shell or
+    /// [`GENERATE_SCHEMA`](schema::GENERATE_SCHEMA) is in `args` before `--`. The program should
+    /// exit without parsing if it returns `true`, [`parse_env`](ParserImpl::parse_env) does it.
+    fn generate(name: &str, args: &Args) -> Result<bool, Self::Error>
+    where
+        Self: 'inv,
+        <Self::Parser<'inv> as Set>::Ctor: Ctor<Opt = opt::Opt>,
+        Self::Error: From<Error>,
+    {
+        let mut words = args
+            .iter()
+            .skip(1)
+            .map(|v| v.to_str())
+            .take_while(|v| v != &Some("--"));
+
+        while let Some(word) = words.next() {
+            match word {
+                Some(schema::GENERATE_SCHEMA) => {
+                    println!("{}", Self::schema(name)?.render());
+                    return Ok(true);
+                }
+                Some(arg) if arg.split('=').next() == Some(completion::GENERATE_COMPLETION) => {
+                    let shell = match arg.split_once('=') {
+                        Some((_, shell)) => Some(shell),
+                        None => words.next().flatten(),
+                    };
+                    let shell = shell
+                        .ok_or_else(|| {
+                            err::fail!("`{}` requires a shell", completion::GENERATE_COMPLETION)
+                        })
+                        .and_then(str::parse::<Shell>)
+                        .map_err(|e| failure::usage_failure(name, e))?;
+
+                    print!("{}", Self::completion(name)?.generate(shell));
+                    return Ok(true);
+                }
+                _ => {}
+            }
+        }
+        Ok(false)
+    }
+
     fn parse(args: Args) -> Result<Self, Self::Error>
     where
         Self: 'inv + Sized;
 
+    /// Parse the arguments of program. If the program is called by the shell for completion
+    /// or with the hidden generate flags, print the candidates or the script and exit, see
+    /// [`complete_env`](ParserImpl::complete_env) and [`generate`](ParserImpl::generate).
     fn parse_env() -> Result<Self, Self::Error>
     where
         Self: 'inv + Sized,
+        <Self::Parser<'inv> as Set>::Ctor: Ctor<Opt = opt::Opt>,
+        Self::Error: From<Error>,
     {
-        Self::parse(Args::from_env())
+        let args = Args::from_env();
+        let name = args
+            .first()
+            .map(std::path::Path::new)
+            .and_then(|v| v.file_name())
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if Self::complete_env(&name)? || Self::generate(&name, &args)? {
+            std::process::exit(0);
+        }
+        Self::parse(args)
+    }
+
+    /// Parse the command line `line` in one string, the first word is the program name, such as
+    /// `Git::parse_str("git clone --depth 1 'my repo'")`.
+    /// The line is split by [`split`](shell::split), an unbalanced quote is reported as a failure.
+    fn parse_str(line: &str) -> Result<Self, Self::Error>
+    where
+        Self: 'inv + Sized,
+        Self::Error: From<Error>,
+    {
+        Self::parse(Args::from(shell::split(line)?))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    use aopt::opt::ConfigValue;
+    use aopt::set::Ctor;
+    use aopt::set::Set;
+    use aopt::set::SetCfg;
+    use aopt::set::SetExt;
+
+    use crate::infer::Infer;
+    use crate::opt::Opt;
+    use crate::prelude::OptSet;
+    use crate::Error;
+
+    /// Insert the option `T` named `name` into `set`, `f` can change the config before the
+    /// option is created.
+    pub fn insert_opt<'a, 'b, T: Infer>(
+        set: &'b mut OptSet<'a>,
+        name: &str,
+        f: impl FnOnce(&mut SetCfg<OptSet<'a>>),
+    ) -> Result<&'b mut Opt, Error> {
+        let mut cfg = SetCfg::<OptSet>::default();
+
+        cfg.set_name(name);
+        f(&mut cfg);
+        T::infer_fill_info(&mut cfg)?;
+
+        let mut opt = set
+            .ctor_mut(&aopt::set::ctor_default_name())?
+            .new_with(cfg)?;
+
+        T::infer_fill_opt(&mut opt)?;
+
+        let uid = set.insert(opt);
+
+        set.opt_mut(uid)
     }
 }
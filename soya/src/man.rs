@@ -0,0 +1,251 @@
+use std::fmt::Write;
+
+use aopt::opt::Index;
+
+use crate::describe::CmdDesc;
+use crate::describe::OptDesc;
+
+/// The hint of positional argument, such as `<repo>` or `[files]...`.
+fn pos_hint(pos: &OptDesc) -> String {
+    let dots = match pos.index() {
+        Some(Index::Forward(_)) => "",
+        Some(Index::Range(start, end)) if end.is_some_and(|end| end <= start + 1) => "",
+        _ => "...",
+    };
+
+    if pos.force() {
+        format!("<{}>{}", pos.name(), dots)
+    } else {
+        format!("[{}]{}", pos.name(), dots)
+    }
+}
+
+/// The manual of a command tree rendered as roff man pages, one page for each command.
+#[derive(Debug, Clone)]
+pub struct Manual {
+    desc: CmdDesc,
+    section: String,
+}
+
+impl Manual {
+    pub fn new(desc: CmdDesc) -> Self {
+        Self {
+            desc,
+            section: "1".to_owned(),
+        }
+    }
+
+    /// The section of manual, default is `1`.
+    pub fn with_section(mut self, section: impl Into<String>) -> Self {
+        self.section = section.into();
+        self
+    }
+
+    pub fn desc(&self) -> &CmdDesc {
+        &self.desc
+    }
+
+    pub fn section(&self) -> &str {
+        &self.section
+    }
+
+    /// Render the man page of the command.
+    pub fn render(&self) -> String {
+        self.render_page(&self.desc, &[self.desc.name()])
+    }
+
+    /// Render the man pages of the command and all the sub commands, return the file name
+    /// such as `git-clone.1` and the content of each page.
+    pub fn pages(&self) -> Vec<(String, String)> {
+        let mut ret = vec![];
+
+        self.collect_pages(&self.desc, &mut vec![], &mut ret);
+        ret
+    }
+
+    fn collect_pages<'a>(
+        &self,
+        cmd: &'a CmdDesc,
+        path: &mut Vec<&'a str>,
+        pages: &mut Vec<(String, String)>,
+    ) {
+        path.push(cmd.name());
+        pages.push((
+            format!("{}.{}", path.join("-"), self.section),
+            self.render_page(cmd, path),
+        ));
+        for sub in cmd.subs() {
+            self.collect_pages(sub, path, pages);
+        }
+        path.pop();
+    }
+
+    fn render_page(&self, cmd: &CmdDesc, path: &[&str]) -> String {
+        let title = path.join("-");
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            ".TH {} {}",
+            escape(&title.to_uppercase()),
+            escape(&self.section)
+        );
+        out.push_str(".SH NAME\n");
+        if cmd.about().is_empty() {
+            let _ = writeln!(out, "{}", escape(&title));
+        } else {
+            let _ = writeln!(
+                out,
+                "{} \\- {}",
+                escape(&title),
+                escape(first_line(cmd.about()))
+            );
+        }
+
+        out.push_str(".SH SYNOPSIS\n");
+        let _ = write!(out, "\\fB{}\\fR", escape(&path.join(" ")));
+        if !cmd.usage().is_empty() {
+            let _ = write!(out, " {}", escape(cmd.usage()));
+        }
+        out.push('\n');
+
+        if !cmd.about().is_empty() {
+            out.push_str(".SH DESCRIPTION\n");
+            let paras: Vec<_> = cmd
+                .about()
+                .split("\n\n")
+                .map(|para| escape(para.trim()))
+                .collect();
+
+            let _ = writeln!(out, "{}", paras.join("\n.PP\n"));
+        }
+
+        if !cmd.opts().is_empty() || !cmd.poss().is_empty() {
+            out.push_str(".SH OPTIONS\n");
+            for pos in cmd.poss() {
+                write_item(&mut out, &pos_hint(pos), pos.help());
+            }
+            for opt in cmd.opts() {
+                write_item(&mut out, opt.hint(), opt.help());
+            }
+        }
+
+        if !cmd.subs().is_empty() {
+            out.push_str(".SH SUBCOMMANDS\n");
+            for sub in cmd.subs() {
+                let page = format!("{}-{}({})", title, sub.name(), self.section);
+
+                write_item(&mut out, &page, first_line(sub.about()));
+            }
+        }
+
+        let envs: Vec<_> = cmd
+            .opts()
+            .iter()
+            .filter_map(|opt| opt.env().map(|env| (env, opt)))
+            .collect();
+
+        if !envs.is_empty() {
+            out.push_str(".SH ENVIRONMENT\n");
+            for (env, opt) in envs {
+                let help = format!("Used as the value of {} if it is not present.", opt.hint());
+
+                write_item(&mut out, env, &help);
+            }
+        }
+        out
+    }
+}
+
+fn write_item(out: &mut String, tag: &str, help: &str) {
+    out.push_str(".TP\n");
+    let _ = writeln!(out, "\\fB{}\\fR", escape(tag));
+    if !help.is_empty() {
+        let _ = writeln!(out, "{}", escape(help));
+    }
+}
+
+fn first_line(val: &str) -> &str {
+    val.lines().next().unwrap_or_default()
+}
+
+/// Escape the text for roff, the line start with `.` or `'` is protected by `\&`.
+fn escape(val: &str) -> String {
+    let mut ret = String::with_capacity(val.len());
+
+    for (i, line) in val.split('\n').enumerate() {
+        if i > 0 {
+            ret.push('\n');
+        }
+        if line.starts_with('.') || line.starts_with('\'') {
+            ret.push_str("\\&");
+        }
+        for ch in line.chars() {
+            match ch {
+                '\\' => ret.push_str("\\e"),
+                '-' => ret.push_str("\\-"),
+                _ => ret.push(ch),
+            }
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Command;
+    use crate::Error;
+
+    fn git() -> Command {
+        let mut clone = Command::new("clone").with_about("Clone a repository.");
+        let _ = clone
+            .opt::<Option<u64>>("--depth")
+            .help("Create a shallow clone");
+        let _ = clone.pos::<String>("repo", 1).help("The repository");
+
+        let mut git = Command::new("git").with_about("The stupid content tracker.");
+        let _ = git
+            .opt::<bool>("--debug")
+            .alias("-d")
+            .env("GIT_DEBUG")
+            .help(".start with dot");
+        let _ = git.sub(clone);
+        git
+    }
+
+    #[test]
+    fn render_man_page() -> Result<(), Error> {
+        let page = Manual::new(git().describe()?).with_section("7").render();
+
+        assert!(page.starts_with(".TH GIT 7\n.SH NAME\ngit \\- The stupid content tracker.\n"));
+        assert!(page.contains(".SH SYNOPSIS\n\\fBgit\\fR"));
+        assert!(page.contains(".SH OPTIONS\n"));
+        assert!(page.contains("\n\\&.start with dot\n"));
+        assert!(
+            page.contains(".SH SUBCOMMANDS\n.TP\n\\fBgit\\-clone(7)\\fR\nClone a repository.\n")
+        );
+        assert!(page.contains(".SH ENVIRONMENT\n.TP\n\\fBGIT_DEBUG\\fR\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn render_page_of_sub_commands() -> Result<(), Error> {
+        let pages = Manual::new(git().describe()?).pages();
+        let names: Vec<_> = pages.iter().map(|(name, _)| name.as_str()).collect();
+
+        assert_eq!(names, ["git.1", "git-clone.1"]);
+        assert!(pages[1].1.starts_with(".TH GIT\\-CLONE 1\n"));
+        assert!(pages[1].1.contains("\\fBgit clone\\fR"));
+        assert!(pages[1].1.contains(".TP\n\\fB<repo>\\fR\nThe repository\n"));
+        assert!(pages[1].1.contains("\\-\\-depth"));
+        assert!(!pages[1].1.contains(".SH SUBCOMMANDS"));
+        Ok(())
+    }
+
+    #[test]
+    fn escape_roff_text() {
+        assert_eq!(escape("a-b\\c"), "a\\-b\\ec");
+        assert_eq!(escape(".x\n'y\nz"), "\\&.x\n\\&'y\nz");
+    }
+}
@@ -1,6 +1,8 @@
 use std::any::TypeId;
 use std::ffi::OsStr;
+use std::ops::RangeInclusive;
 
+use crate::completion::ValueHint;
 use crate::err::err;
 use crate::err::Error;
 
@@ -34,6 +36,16 @@ pub struct Opt {
     ignore_name: bool,
     ignore_alias: bool,
     ignore_index: bool,
+    arity: Option<RangeInclusive<usize>>,
+    conflicts: Vec<String>,
+    requires: Vec<String>,
+    required_if: Vec<String>,
+    possible_values: Option<Vec<String>>,
+    value_hint: ValueHint,
+    hidden: bool,
+    env: Option<String>,
+    value_name: Option<String>,
+    default_value: Option<String>,
 }
 
 impl Opt {
@@ -53,6 +65,16 @@ impl Opt {
             ignore_name: Default::default(),
             ignore_alias: Default::default(),
             ignore_index: Default::default(),
+            arity: Default::default(),
+            conflicts: Default::default(),
+            requires: Default::default(),
+            required_if: Default::default(),
+            possible_values: Default::default(),
+            value_hint: Default::default(),
+            hidden: Default::default(),
+            env: Default::default(),
+            value_name: Default::default(),
+            default_value: Default::default(),
         }
     }
 
@@ -131,6 +153,56 @@ impl Opt {
         self
     }
 
+    pub fn with_arity(mut self, arity: Option<RangeInclusive<usize>>) -> Self {
+        self.arity = arity;
+        self
+    }
+
+    pub fn with_conflicts(mut self, conflicts: Vec<String>) -> Self {
+        self.conflicts = conflicts;
+        self
+    }
+
+    pub fn with_requires(mut self, requires: Vec<String>) -> Self {
+        self.requires = requires;
+        self
+    }
+
+    pub fn with_required_if(mut self, required_if: Vec<String>) -> Self {
+        self.required_if = required_if;
+        self
+    }
+
+    pub fn with_possible_values(mut self, possible_values: Option<Vec<String>>) -> Self {
+        self.possible_values = possible_values;
+        self
+    }
+
+    pub fn with_value_hint(mut self, value_hint: ValueHint) -> Self {
+        self.value_hint = value_hint;
+        self
+    }
+
+    pub fn with_hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    pub fn with_env(mut self, env: Option<String>) -> Self {
+        self.env = env;
+        self
+    }
+
+    pub fn with_value_name(mut self, value_name: Option<String>) -> Self {
+        self.value_name = value_name;
+        self
+    }
+
+    pub fn with_default_value(mut self, default_value: Option<String>) -> Self {
+        self.default_value = default_value;
+        self
+    }
+
     pub fn set_name(&mut self, name: impl Into<String>) -> &mut Self {
         self.name = name.into();
         self
@@ -191,6 +263,114 @@ impl Opt {
         self.accessor = value;
         self
     }
+
+    pub fn set_arity(&mut self, arity: Option<RangeInclusive<usize>>) -> &mut Self {
+        self.arity = arity;
+        self
+    }
+
+    /// Number of arguments consumed by the option, `None` means the option takes one value as usual.
+    pub fn arity(&self) -> Option<&RangeInclusive<usize>> {
+        self.arity.as_ref()
+    }
+
+    pub fn add_conflict(&mut self, name: impl Into<String>) -> &mut Self {
+        self.conflicts.push(name.into());
+        self
+    }
+
+    pub fn add_require(&mut self, name: impl Into<String>) -> &mut Self {
+        self.requires.push(name.into());
+        self
+    }
+
+    /// The option is required if `name` is present, or if it has the value, such as `--format=json`.
+    pub fn add_required_if(&mut self, name: impl Into<String>) -> &mut Self {
+        self.required_if.push(name.into());
+        self
+    }
+
+    pub fn set_possible_values(&mut self, possible_values: Option<Vec<String>>) -> &mut Self {
+        self.possible_values = possible_values;
+        self
+    }
+
+    pub fn set_value_hint(&mut self, value_hint: ValueHint) -> &mut Self {
+        self.value_hint = value_hint;
+        self
+    }
+
+    pub fn set_hidden(&mut self, hidden: bool) -> &mut Self {
+        self.hidden = hidden;
+        self
+    }
+
+    pub fn set_env(&mut self, env: impl Into<String>) -> &mut Self {
+        self.env = Some(env.into());
+        self
+    }
+
+    /// The environment variable used as value if the option is not present, see
+    /// [`env_values`](crate::env::env_values).
+    pub fn env(&self) -> Option<&str> {
+        self.env.as_deref()
+    }
+
+    pub fn set_value_name(&mut self, value_name: impl Into<String>) -> &mut Self {
+        self.value_name = Some(value_name.into());
+        self
+    }
+
+    pub fn set_default_value(&mut self, default_value: impl Into<String>) -> &mut Self {
+        self.default_value = Some(default_value.into());
+        self
+    }
+
+    /// The styles of option, such as [`Style::Argument`].
+    pub fn styles(&self) -> &[Style] {
+        &self.styles
+    }
+
+    /// The name of value displayed in the document, such as `<u64>`.
+    pub fn value_name(&self) -> Option<&str> {
+        self.value_name.as_deref()
+    }
+
+    /// The value used if the option is not present, it is only displayed in the document.
+    pub fn default_value(&self) -> Option<&str> {
+        self.default_value.as_deref()
+    }
+
+    /// The values accepted by the option, such as the variants of an enum.
+    pub fn possible_values(&self) -> Option<&[String]> {
+        self.possible_values.as_deref()
+    }
+
+    /// The kind of value the shell should complete for the option.
+    pub fn value_hint(&self) -> ValueHint {
+        self.value_hint
+    }
+
+    /// Hidden option is not displayed in help message and completion.
+    pub fn hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Options can not be used together with this option.
+    pub fn conflicts(&self) -> &[String] {
+        &self.conflicts
+    }
+
+    /// Options must be present when this option is present.
+    pub fn requires(&self) -> &[String] {
+        &self.requires
+    }
+
+    /// This option must be present when any of these options is present, or has the value
+    /// such as `--format=json`.
+    pub fn required_if(&self) -> &[String] {
+        &self.required_if
+    }
 }
 
 impl aopt::opt::Opt for Opt {
@@ -384,7 +564,7 @@ impl aopt::opt::Opt for Opt {
     }
 }
 
-fn gen_hint(
+pub(crate) fn gen_hint(
     hint: Option<impl Into<String>>,
     n: &str,
     idx: Option<&Index>,
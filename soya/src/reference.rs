@@ -0,0 +1,142 @@
+use std::fmt::Write;
+
+use aopt::opt::Style;
+
+use crate::describe::CmdDesc;
+use crate::describe::OptDesc;
+
+/// The Markdown reference of a command tree, one section for each command.
+#[derive(Debug, Clone)]
+pub struct Reference {
+    desc: CmdDesc,
+}
+
+impl Reference {
+    pub fn new(desc: CmdDesc) -> Self {
+        Self { desc }
+    }
+
+    pub fn desc(&self) -> &CmdDesc {
+        &self.desc
+    }
+
+    /// Render the reference of the command and all the sub commands, one section per command.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        render_section(&self.desc, &mut vec![], &mut out);
+        out
+    }
+}
+
+fn render_section<'a>(desc: &'a CmdDesc, path: &mut Vec<&'a str>, out: &mut String) {
+    path.push(desc.name());
+
+    let cmd = path.join(" ");
+    let level = if path.len() == 1 { "#" } else { "##" };
+
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    let _ = writeln!(out, "{} `{}`\n", level, cmd);
+    if !desc.about().is_empty() {
+        let _ = writeln!(out, "{}\n", desc.about().trim());
+    }
+    out.push_str("```text\n");
+    let _ = writeln!(out, "{}", format!("{} {}", cmd, desc.usage()).trim_end());
+    out.push_str("```\n");
+    if !desc.subs().is_empty() {
+        out.push_str("\n**Commands**\n\n");
+        for sub in desc.subs() {
+            let about = sub.about().lines().next().unwrap_or_default();
+
+            if about.is_empty() {
+                let _ = writeln!(out, "- `{} {}`", cmd, sub.name());
+            } else {
+                let _ = writeln!(out, "- `{} {}`: {}", cmd, sub.name(), about);
+            }
+        }
+    }
+    write_table(out, "Arguments", desc.poss());
+    write_table(out, "Options", desc.opts());
+    for sub in desc.subs() {
+        render_section(sub, path, out);
+    }
+    path.pop();
+}
+
+fn write_table(out: &mut String, title: &str, items: &[OptDesc]) {
+    if items.is_empty() {
+        return;
+    }
+    let code = |val: &str| {
+        if val.is_empty() {
+            "-".to_owned()
+        } else {
+            format!("`{}`", escape(val))
+        }
+    };
+
+    let _ = writeln!(out, "\n**{}**\n", title);
+    out.push_str("| Name | Aliases | Value | Default | Required | Description |\n");
+    out.push_str("| --- | --- | --- | --- | --- | --- |\n");
+    for item in items {
+        let aliases: Vec<_> = item.aliases().iter().map(|v| code(v)).collect();
+        let value = match item.value_name() {
+            Some(value) if !item.mat_style(Style::Boolean) => value,
+            _ => "",
+        };
+
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} |",
+            code(item.name()),
+            if aliases.is_empty() {
+                "-".to_owned()
+            } else {
+                aliases.join(", ")
+            },
+            code(value),
+            code(item.default_value().unwrap_or_default()),
+            if item.force() { "yes" } else { "no" },
+            escape(&item.help().replace('\n', " ")),
+        );
+    }
+}
+
+/// Escape the `|` in the cell of table.
+fn escape(val: &str) -> String {
+    val.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Command;
+    use crate::Error;
+
+    #[test]
+    fn render_reference_of_command_tree() -> Result<(), Error> {
+        let mut clone = Command::new("clone").with_about("Clone a repository.\n\nMore details.");
+        let _ = clone
+            .opt::<Option<u64>>("--depth")
+            .default_value(1u64)
+            .help("Create a shallow clone");
+        let _ = clone.pos::<String>("repo", 1).help("The repository");
+
+        let mut git = Command::new("git").with_about("The stupid content tracker.");
+        let _ = git.opt::<bool>("--debug").alias("-d").help("a | b");
+        let _ = git.sub(clone);
+
+        let out = Reference::new(git.describe()?).render();
+
+        assert!(out.starts_with("# `git`\n\nThe stupid content tracker.\n\n```text\ngit "));
+        assert!(out.contains("**Commands**\n\n- `git clone`: Clone a repository.\n"));
+        assert!(out.contains("| `--debug` | `-d` | - | `false` | no | a \\| b |"));
+        assert!(out.contains("\n## `git clone`\n\nClone a repository.\n\nMore details.\n"));
+        assert!(out.contains("**Arguments**\n"));
+        assert!(out.contains("| `repo` | - | `<String>` | - | yes | The repository |"));
+        assert!(out.contains("| `--depth` | - | `<u64>` | `1` | no | Create a shallow clone |"));
+        Ok(())
+    }
+}
@@ -0,0 +1,253 @@
+use aopt::opt::Opt as _;
+use aopt::prelude::Args;
+use aopt::set::Ctor;
+use aopt::set::Set;
+
+use crate::env::present_values;
+use crate::err::fail;
+use crate::opt::Opt;
+use crate::Error;
+
+/// A named group of options, such as "exactly one of `--file`, `--stdin` or `--url`".
+#[derive(Debug, Clone, Default)]
+pub struct Group {
+    name: String,
+    opts: Vec<String>,
+    required: bool,
+    multiple: bool,
+}
+
+impl Group {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_opts<I, T>(mut self, opts: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.opts = opts.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// At least one option of the group must be present.
+    pub fn with_required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// More than one option of the group can be present.
+    pub fn with_multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn opts(&self) -> &[String] {
+        &self.opts
+    }
+
+    pub fn required(&self) -> bool {
+        self.required
+    }
+
+    pub fn multiple(&self) -> bool {
+        self.multiple
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.opts.iter().any(|v| v == name)
+    }
+
+    /// Describe the rule of the group, such as `exactly one of`.
+    pub fn rule(&self) -> &'static str {
+        match (self.required, self.multiple) {
+            (true, false) => "exactly one of",
+            (true, true) => "at least one of",
+            (false, false) => "at most one of",
+            (false, true) => "any of",
+        }
+    }
+}
+
+fn find_opt<'a, S>(set: &'a S, name: &str) -> Result<&'a Opt, Error>
+where
+    S: Set,
+    S::Ctor: Ctor<Opt = Opt>,
+{
+    set.iter()
+        .find(|opt| opt.name() == name || opt.mat_alias(name))
+        .ok_or_else(|| fail!("can not find option `{}` in relations", name))
+}
+
+fn join_names(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|v| format!("`{}`", v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Check the relations between options and the `groups` according to the `matched` state,
+/// call it after the `args` parsed.
+pub fn check_relations<S>(set: &S, args: &Args, groups: &[Group]) -> Result<(), Error>
+where
+    S: Set,
+    S::Ctor: Ctor<Opt = Opt>,
+{
+    match relation_failures(set, args, groups)?.into_iter().next() {
+        Some(failure) => Err(failure),
+        None => Ok(()),
+    }
+}
+
+/// Return all the relations not satisfied, the error is returned if any option not exist.
+///
+/// The condition of `required_if` is checked against the values in `args`, such as
+/// `--format=json` is satisfied by `--format json`.
+pub fn relation_failures<S>(set: &S, args: &Args, groups: &[Group]) -> Result<Vec<Error>, Error>
+where
+    S: Set,
+    S::Ctor: Ctor<Opt = Opt>,
+{
+    let present = present_values(set, args);
+    let mut failures = vec![];
+
+    for opt in set.iter() {
+        let name = opt.name();
+
+        if opt.matched() {
+            for other in opt.conflicts() {
+                if find_opt(set, other)?.matched() {
+                    failures.push(fail!("option `{}` cannot be used with `{}`", name, other));
+                }
+            }
+            for other in opt.requires() {
+                if !find_opt(set, other)?.matched() {
+                    failures.push(fail!(
+                        "option `{}` requires `{}`, but it was not provided",
+                        name,
+                        other
+                    ));
+                }
+            }
+        } else {
+            for cond in opt.required_if() {
+                match cond.split_once('=') {
+                    Some((other, value)) => {
+                        let other_opt = find_opt(set, other)?;
+
+                        if present
+                            .iter()
+                            .any(|(v, v_val)| v.uid() == other_opt.uid() && *v_val == Some(value))
+                        {
+                            failures.push(fail!(
+                                "option `{}` is required when `{}` is `{}`",
+                                name,
+                                other,
+                                value
+                            ));
+                        }
+                    }
+                    None => {
+                        if find_opt(set, cond)?.matched() {
+                            failures.push(fail!(
+                                "option `{}` is required when `{}` is present",
+                                name,
+                                cond
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    for group in groups {
+        let mut matched = vec![];
+
+        for name in group.opts() {
+            if find_opt(set, name)?.matched() {
+                matched.push(name.as_str());
+            }
+        }
+        if group.required() && matched.is_empty() {
+            let names: Vec<_> = group.opts().iter().map(String::as_str).collect();
+
+            failures.push(fail!(
+                "{} {} of group `{}` is required",
+                group.rule(),
+                join_names(&names),
+                group.name()
+            ));
+        }
+        if !group.multiple() && matched.len() > 1 {
+            failures.push(fail!(
+                "option `{}` cannot be used with `{}` of group `{}`",
+                matched[0],
+                matched[1],
+                group.name()
+            ));
+        }
+    }
+    Ok(failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::Command;
+    use crate::prelude::Args;
+    use crate::Error;
+
+    fn failure(cmd: &Command, args: &[&str]) -> String {
+        cmd.parse(Args::from(args.to_vec()))
+            .map(|_| String::default())
+            .unwrap_or_else(|e| e.to_string())
+    }
+
+    #[test]
+    fn check_conflicts_and_requires() -> Result<(), Error> {
+        let mut tool = Command::new("tool");
+        let _ = tool.opt::<bool>("--json").conflicts_with("--yaml");
+        let _ = tool.opt::<bool>("--yaml");
+        let _ = tool.opt::<Option<String>>("--user").requires("--token");
+        let _ = tool.opt::<Option<String>>("--token");
+
+        tool.parse(Args::from(vec!["tool", "--json", "--user=a", "--token=b"]))?;
+        assert!(failure(&tool, &["tool", "--json", "--yaml"])
+            .contains("option `--json` cannot be used with `--yaml`"));
+        assert!(failure(&tool, &["tool", "--user", "a"])
+            .contains("option `--user` requires `--token`, but it was not provided"));
+        Ok(())
+    }
+
+    #[test]
+    fn check_required_if_value() -> Result<(), Error> {
+        let mut tool = Command::new("tool");
+        let _ = tool.opt::<Option<String>>("--format");
+        let _ = tool
+            .opt::<Option<String>>("--schema")
+            .required_if("--format=json");
+        let _ = tool.opt::<Option<String>>("--out").required_if("--format");
+
+        tool.parse(Args::from(vec!["tool"]))?;
+        tool.parse(Args::from(vec!["tool", "--format", "toml", "--out=x"]))?;
+        tool.parse(Args::from(vec![
+            "tool",
+            "--format=json",
+            "--out=x",
+            "--schema=s",
+        ]))?;
+        assert!(failure(&tool, &["tool", "--format", "json", "--out=x"])
+            .contains("option `--schema` is required when `--format` is `json`"));
+        assert!(failure(&tool, &["tool", "--format=toml"])
+            .contains("option `--out` is required when `--format` is present"));
+        Ok(())
+    }
+}
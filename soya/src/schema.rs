@@ -0,0 +1,171 @@
+use crate::describe::CmdDesc;
+use crate::describe::OptDesc;
+use crate::json::Json;
+
+/// The hidden flag print the schema of command, see
+/// [`ParserImpl::generate`](crate::ParserImpl::generate).
+pub const GENERATE_SCHEMA: &str = "--generate-schema";
+
+fn opt_json(opt: &OptDesc) -> Json {
+    Json::Object(vec![
+        ("name".to_owned(), Json::str(opt.name())),
+        ("aliases".to_owned(), Json::strs(opt.aliases())),
+        (
+            "styles".to_owned(),
+            Json::strs(opt.styles().iter().map(|v| format!("{:?}", v))),
+        ),
+        (
+            "index".to_owned(),
+            Json::opt_str(opt.index().map(|v| v.to_string())),
+        ),
+        ("force".to_owned(), Json::Bool(opt.force())),
+        (
+            "action".to_owned(),
+            Json::str(format!("{:?}", opt.action())),
+        ),
+        ("value".to_owned(), Json::opt_str(opt.value_name())),
+        ("help".to_owned(), Json::str(opt.help())),
+        ("default".to_owned(), Json::opt_str(opt.default_value())),
+        ("env".to_owned(), Json::opt_str(opt.env())),
+        (
+            "possible_values".to_owned(),
+            opt.possible_values().map(Json::strs).unwrap_or_default(),
+        ),
+    ])
+}
+
+fn cmd_json(cmd: &CmdDesc) -> Json {
+    Json::Object(vec![
+        ("name".to_owned(), Json::str(cmd.name())),
+        ("about".to_owned(), Json::str(cmd.about())),
+        (
+            "options".to_owned(),
+            Json::Array(cmd.opts().iter().chain(cmd.poss()).map(opt_json).collect()),
+        ),
+        (
+            "subcommands".to_owned(),
+            Json::Array(cmd.subs().iter().map(cmd_json).collect()),
+        ),
+    ])
+}
+
+/// The JSON description of a command tree, it can be loaded by
+/// [`DynCommand::from_json`](crate::dynamic::DynCommand::from_json).
+#[derive(Debug, Clone)]
+pub struct Schema {
+    desc: CmdDesc,
+}
+
+impl Schema {
+    pub fn new(desc: CmdDesc) -> Self {
+        Self { desc }
+    }
+
+    pub fn desc(&self) -> &CmdDesc {
+        &self.desc
+    }
+
+    /// Convert the command and all the sub commands to [`Json`], such as
+    ///
+    /// ```json
+    /// {
+    ///   "name": "git",
+    ///   "about": "The stupid content tracker.",
+    ///   "options": [
+    ///     {
+    ///       "name": "--debug",
+    ///       "aliases": [],
+    ///       "styles": ["Combined", "Boolean"],
+    ///       "index": null,
+    ///       "force": false,
+    ///       "action": "Set",
+    ///       "value": "<bool>",
+    ///       "help": "",
+    ///       "default": "false",
+    ///       "env": null,
+    ///       "possible_values": null
+    ///     }
+    ///   ],
+    ///   "subcommands": []
+    /// }
+    /// ```
+    pub fn to_json(&self) -> Json {
+        cmd_json(&self.desc)
+    }
+
+    /// Render the JSON of command with two spaces indent.
+    pub fn render(&self) -> String {
+        self.to_json().pretty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Command;
+    #[cfg(feature = "serde_json")]
+    use crate::dynamic::DynCommand;
+    #[cfg(feature = "serde_json")]
+    use crate::prelude::Args;
+    use crate::Error;
+
+    fn git() -> Command {
+        let mut clone = Command::new("clone").with_about("Clone a repository.");
+        let _ = clone.opt::<Option<u64>>("--depth").default_value(1u64);
+        let _ = clone.pos::<String>("repo", 1);
+
+        let mut git = Command::new("git").with_about("The stupid content tracker.");
+        let _ = git
+            .opt::<bool>("--debug")
+            .alias("-d")
+            .env("GIT_DEBUG")
+            .help("Print debug message");
+        let _ = git.sub(clone);
+        git
+    }
+
+    #[test]
+    fn export_command_tree() -> Result<(), Error> {
+        let json = Schema::new(git().describe()?).to_json();
+        let opts = json.get("options").and_then(Json::as_array).unwrap();
+        let debug = opts
+            .iter()
+            .find(|v| v.get("name").and_then(Json::as_str) == Some("--debug"))
+            .unwrap();
+
+        assert_eq!(json.get("name").and_then(Json::as_str), Some("git"));
+        assert_eq!(
+            json.get("about").and_then(Json::as_str),
+            Some("The stupid content tracker.")
+        );
+        assert_eq!(debug.get("aliases"), Some(&Json::strs(["-d"])));
+        assert_eq!(debug.get("force").and_then(Json::as_bool), Some(false));
+        assert_eq!(debug.get("default").and_then(Json::as_str), Some("false"));
+        assert_eq!(debug.get("env").and_then(Json::as_str), Some("GIT_DEBUG"));
+        assert_eq!(
+            debug.get("help").and_then(Json::as_str),
+            Some("Print debug message")
+        );
+
+        let subs = json.get("subcommands").and_then(Json::as_array).unwrap();
+
+        assert_eq!(subs.len(), 1);
+        assert_eq!(subs[0].get("name").and_then(Json::as_str), Some("clone"));
+        Ok(())
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn load_rendered_schema() -> Result<(), Error> {
+        let text = Schema::new(git().describe()?).render();
+        let git = DynCommand::from_schema(&text)?;
+        let values = git.parse(Args::from(vec!["git", "-d", "clone", "url"]))?;
+        let (name, sub) = values.sub().unwrap();
+
+        assert_eq!(git.about(), "The stupid content tracker.");
+        assert_eq!(values.get_bool("--debug"), Some(true));
+        assert_eq!(name, "clone");
+        assert_eq!(sub.get_str("repo"), Some("url"));
+        Ok(())
+    }
+}
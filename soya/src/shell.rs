@@ -0,0 +1,108 @@
+use crate::err::fail;
+use crate::Error;
+
+/// Split the command line into words with the POSIX shell quoting rules, there is no
+/// expansion of variable, glob or tilde.
+///
+/// - the words are separated by white spaces,
+/// - the characters in single quotes are kept literally,
+/// - in double quotes, the backslash only escapes `$`, `` ` ``, `"`, `\` and new line,
+/// - out of quotes, the backslash escapes any character and a backslash new line is removed.
+///
+/// Return a failure if the quote is not closed or the line ends with a backslash.
+///
+/// ```
+/// # use soya::shell::split;
+/// # use soya::err::Error;
+/// #
+/// # fn main() -> Result<(), Error> {
+/// assert_eq!(
+///     split(r#"clone --depth 1 'my repo' "a \"b\"" c\ d ''"#)?,
+///     ["clone", "--depth", "1", "my repo", "a \"b\"", "c d", ""]
+/// );
+/// assert!(split("clone 'my repo").is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub fn split(line: &str) -> Result<Vec<String>, Error> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => word.push(ch),
+                        None => return Err(fail!("unterminated single quote in `{}`", line)),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('\n') => {}
+                            Some(ch @ ('$' | '`' | '"' | '\\')) => word.push(ch),
+                            Some(ch) => {
+                                word.push('\\');
+                                word.push(ch);
+                            }
+                            None => return Err(fail!("unterminated double quote in `{}`", line)),
+                        },
+                        Some(ch) => word.push(ch),
+                        None => return Err(fail!("unterminated double quote in `{}`", line)),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(ch) => word.get_or_insert_with(String::new).push(ch),
+                None => return Err(fail!("unexpected end of line after `\\` in `{}`", line)),
+            },
+            ch if ch.is_whitespace() => words.extend(word.take()),
+            ch => word.get_or_insert_with(String::new).push(ch),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Quote the word so that it is split as one word by [`split`], the word only contains
+/// safe characters such as `--depth=1` is kept as it is.
+pub fn quote(word: &str) -> String {
+    let safe = |ch: char| ch.is_ascii_alphanumeric() || "_-+=.,:/@%".contains(ch);
+
+    if !word.is_empty() && word.chars().all(safe) {
+        word.to_owned()
+    } else {
+        format!("'{}'", word.replace('\'', r#"'\''"#))
+    }
+}
+
+/// Join the words into a command line with each word quoted by [`quote`].
+///
+/// ```
+/// # use soya::shell::{join, split};
+/// # use soya::err::Error;
+/// #
+/// # fn main() -> Result<(), Error> {
+/// let line = join(["clone", "--depth=1", "my repo", "it's", ""]);
+///
+/// assert_eq!(line, r#"clone --depth=1 'my repo' 'it'\''s' ''"#);
+/// assert_eq!(split(&line)?, ["clone", "--depth=1", "my repo", "it's", ""]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn join<T: AsRef<str>>(words: impl IntoIterator<Item = T>) -> String {
+    let words: Vec<_> = words.into_iter().map(|v| quote(v.as_ref())).collect();
+
+    words.join(" ")
+}
@@ -0,0 +1,513 @@
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::net::SocketAddr;
+use std::num::NonZeroI128;
+use std::num::NonZeroI16;
+use std::num::NonZeroI32;
+use std::num::NonZeroI64;
+use std::num::NonZeroI8;
+use std::num::NonZeroIsize;
+use std::num::NonZeroU128;
+use std::num::NonZeroU16;
+use std::num::NonZeroU32;
+use std::num::NonZeroU64;
+use std::num::NonZeroU8;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::ops::RangeFrom;
+use std::ops::RangeInclusive;
+use std::ops::RangeTo;
+use std::ops::RangeToInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use aopt::opt::Pos;
+
+use crate::arity::VALUE_SEPARATOR;
+use crate::completion::Shell;
+use crate::err::fail;
+use crate::shell;
+use crate::value::ByteSize;
+use crate::value::Delimited;
+use crate::value::FromStrValue;
+use crate::value::Multi;
+use crate::value::Radix;
+use crate::value::RadixInt;
+use crate::value::Unique;
+use crate::Error;
+
+/// Convert the parsed value back to the raw values of argument, it is the reverse of
+/// [`Infer`](crate::infer::Infer).
+pub trait ToRaw {
+    /// The raw values, one for each time the option is present.
+    fn to_raw(&self) -> Vec<OsString>;
+
+    /// Append the option `name` with the values, such as `--depth=1`. The value is joined
+    /// to the name so that it is not confused with an option even if it starts with `-`.
+    fn push_opt(&self, name: &str, args: &mut Vec<OsString>) {
+        for raw in self.to_raw() {
+            let mut arg = OsString::from(name);
+
+            arg.push("=");
+            arg.push(raw);
+            args.push(arg);
+        }
+    }
+
+    /// Append the values of positional argument. The value starts with `-` is written after
+    /// `--` so that it is not parsed as an option, the command needs a
+    /// [`Stop`](aopt::value::Stop) option to accept `--`.
+    fn push_pos(&self, args: &mut Vec<OsString>) {
+        for raw in self.to_raw() {
+            if raw.as_encoded_bytes().starts_with(b"-") && !args.iter().any(|v| v == "--") {
+                args.push(OsString::from("--"));
+            }
+            args.push(raw);
+        }
+    }
+}
+
+/// Join the raw values by `sep` into one raw value, such as the elements of tuple which are
+/// joined by [`VALUE_SEPARATOR`] same as [`group_values`](crate::arity::group_values).
+fn join_raw<I>(values: I, sep: &str) -> OsString
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let mut ret = OsString::new();
+
+    for (i, value) in values.into_iter().enumerate() {
+        if i > 0 {
+            ret.push(sep);
+        }
+        ret.push(value);
+    }
+    ret
+}
+
+/// The boolean option is present only if the value is `true`.
+impl ToRaw for bool {
+    fn to_raw(&self) -> Vec<OsString> {
+        vec![OsString::from(self.to_string())]
+    }
+
+    fn push_opt(&self, name: &str, args: &mut Vec<OsString>) {
+        if *self {
+            args.push(OsString::from(name));
+        }
+    }
+}
+
+macro_rules! impl_to_raw_for {
+    ($($type:ty),+ $(,)?) => {
+        $(
+            impl ToRaw for $type {
+                fn to_raw(&self) -> Vec<OsString> {
+                    vec![OsString::from(self.to_string())]
+                }
+            }
+        )+
+    };
+}
+
+impl_to_raw_for!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, char, String,
+);
+
+impl_to_raw_for!(
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize,
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize,
+);
+
+impl_to_raw_for!(IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, Shell, ByteSize);
+
+impl<T: RadixInt + Display> ToRaw for Radix<T> {
+    fn to_raw(&self) -> Vec<OsString> {
+        vec![OsString::from(self.0.to_string())]
+    }
+}
+
+impl<T: Display> ToRaw for FromStrValue<T> {
+    fn to_raw(&self) -> Vec<OsString> {
+        vec![OsString::from(self.0.to_string())]
+    }
+}
+
+/// The duration is written in whole seconds or nanoseconds, such as `30s` or `1500000000ns`.
+impl ToRaw for Duration {
+    fn to_raw(&self) -> Vec<OsString> {
+        if self.subsec_nanos() == 0 {
+            vec![OsString::from(format!("{}s", self.as_secs()))]
+        } else {
+            vec![OsString::from(format!("{}ns", self.as_nanos()))]
+        }
+    }
+}
+
+impl ToRaw for OsString {
+    fn to_raw(&self) -> Vec<OsString> {
+        vec![self.clone()]
+    }
+}
+
+impl ToRaw for PathBuf {
+    fn to_raw(&self) -> Vec<OsString> {
+        vec![self.clone().into_os_string()]
+    }
+}
+
+impl<T: ToRaw> ToRaw for Pos<T> {
+    fn to_raw(&self) -> Vec<OsString> {
+        self.0.to_raw()
+    }
+
+    fn push_opt(&self, name: &str, args: &mut Vec<OsString>) {
+        self.0.push_opt(name, args)
+    }
+
+    fn push_pos(&self, args: &mut Vec<OsString>) {
+        self.0.push_pos(args)
+    }
+}
+
+/// The option is not present if the value is [`None`].
+impl<T: ToRaw> ToRaw for Option<T> {
+    fn to_raw(&self) -> Vec<OsString> {
+        self.as_ref().map(T::to_raw).unwrap_or_default()
+    }
+
+    fn push_opt(&self, name: &str, args: &mut Vec<OsString>) {
+        if let Some(value) = self {
+            value.push_opt(name, args);
+        }
+    }
+
+    fn push_pos(&self, args: &mut Vec<OsString>) {
+        if let Some(value) = self {
+            value.push_pos(args);
+        }
+    }
+}
+
+/// The option is present once for each value.
+impl<T: ToRaw> ToRaw for Vec<T> {
+    fn to_raw(&self) -> Vec<OsString> {
+        self.iter().flat_map(T::to_raw).collect()
+    }
+
+    fn push_opt(&self, name: &str, args: &mut Vec<OsString>) {
+        for value in self {
+            value.push_opt(name, args);
+        }
+    }
+
+    fn push_pos(&self, args: &mut Vec<OsString>) {
+        for value in self {
+            value.push_pos(args);
+        }
+    }
+}
+
+/// Implement [`ToRaw`] for collection type same as [`Vec`].
+macro_rules! impl_to_raw_for_collection {
+    ($($coll:ident),+) => {
+        $(
+            impl<T: ToRaw> ToRaw for $coll<T> {
+                fn to_raw(&self) -> Vec<OsString> {
+                    self.iter().flat_map(T::to_raw).collect()
+                }
+
+                fn push_opt(&self, name: &str, args: &mut Vec<OsString>) {
+                    for value in self {
+                        value.push_opt(name, args);
+                    }
+                }
+
+                fn push_pos(&self, args: &mut Vec<OsString>) {
+                    for value in self {
+                        value.push_pos(args);
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_to_raw_for_collection!(VecDeque, HashSet, BTreeSet);
+
+impl<S: ToRaw> ToRaw for Unique<S> {
+    fn to_raw(&self) -> Vec<OsString> {
+        self.0.to_raw()
+    }
+
+    fn push_opt(&self, name: &str, args: &mut Vec<OsString>) {
+        self.0.push_opt(name, args)
+    }
+
+    fn push_pos(&self, args: &mut Vec<OsString>) {
+        self.0.push_pos(args)
+    }
+}
+
+/// The values are joined by `SEP`, the `SEP` and `\` in value are escaped by `\`.
+impl<T: ToRaw, const SEP: char> ToRaw for Delimited<T, SEP> {
+    fn to_raw(&self) -> Vec<OsString> {
+        if self.0.is_empty() {
+            return vec![];
+        }
+        let items: Vec<_> = self
+            .0
+            .iter()
+            .flat_map(T::to_raw)
+            // the value of delimited is parsed from string, so it is always valid UTF-8
+            .map(|raw| {
+                raw.to_string_lossy()
+                    .replace('\\', "\\\\")
+                    .replace(SEP, &format!("\\{}", SEP))
+            })
+            .collect();
+
+        vec![OsString::from(items.join(&SEP.to_string()))]
+    }
+}
+
+/// The values are written in one argument joined by [`VALUE_SEPARATOR`], such as
+/// `--point=1\u{1f}2`, it is not split again by [`group_values`](crate::arity::group_values).
+/// A single value of variable arity is written as `--point=1`, it still consumes the
+/// following arguments which are not options.
+impl<T: ToRaw, const MIN: usize, const MAX: usize> ToRaw for Multi<T, MIN, MAX> {
+    fn to_raw(&self) -> Vec<OsString> {
+        let sep = VALUE_SEPARATOR.to_string();
+
+        vec![join_raw(self.0.iter().flat_map(T::to_raw), &sep)]
+    }
+}
+
+impl<T: ToRaw, const N: usize> ToRaw for [T; N] {
+    fn to_raw(&self) -> Vec<OsString> {
+        let sep = VALUE_SEPARATOR.to_string();
+
+        vec![join_raw(self.iter().flat_map(T::to_raw), &sep)]
+    }
+}
+
+/// Implement [`ToRaw`] for tuple, the elements are joined same as [`Multi`].
+macro_rules! impl_to_raw_for_tuple {
+    ($($type:ident . $index:tt),+) => {
+        impl<$($type: ToRaw),+> ToRaw for ($($type,)+) {
+            fn to_raw(&self) -> Vec<OsString> {
+                let sep = VALUE_SEPARATOR.to_string();
+                let values = [$(self.$index.to_raw()),+];
+
+                vec![join_raw(values.into_iter().flatten(), &sep)]
+            }
+        }
+    };
+}
+
+impl_to_raw_for_tuple!(A.0, B.1);
+impl_to_raw_for_tuple!(A.0, B.1, C.2);
+impl_to_raw_for_tuple!(A.0, B.1, C.2, D.3);
+impl_to_raw_for_tuple!(A.0, B.1, C.2, D.3, E.4);
+impl_to_raw_for_tuple!(A.0, B.1, C.2, D.3, E.4, F.5);
+
+/// Implement [`ToRaw`] for range type, such as `10..=20` for `RangeInclusive`.
+macro_rules! impl_to_raw_for_range {
+    ($range:ident, |$value:ident| [$($part:expr),+]) => {
+        impl<T: ToRaw> ToRaw for $range<T> {
+            fn to_raw(&self) -> Vec<OsString> {
+                let $value = self;
+
+                vec![join_raw([$($part),+].into_iter().flatten(), "")]
+            }
+        }
+    };
+}
+
+impl_to_raw_for_range!(Range, |v| [
+    v.start.to_raw(),
+    vec!["..".into()],
+    v.end.to_raw()
+]);
+impl_to_raw_for_range!(RangeInclusive, |v| [
+    v.start().to_raw(),
+    vec!["..=".into()],
+    v.end().to_raw()
+]);
+impl_to_raw_for_range!(RangeFrom, |v| [v.start.to_raw(), vec!["..".into()]]);
+impl_to_raw_for_range!(RangeTo, |v| [vec!["..".into()], v.end.to_raw()]);
+impl_to_raw_for_range!(RangeToInclusive, |v| [vec!["..=".into()], v.end.to_raw()]);
+
+/// Reproduce the command line of a parsed value, such that parsing the arguments returns
+/// an equal value. The options are written with their names, the positional arguments
+/// follow in the order of their index, and the sub command comes last with its own arguments.
+///
+/// Derive it by `#[derive(ToArgs)]` with the same attributes of the parser, or implement it
+/// by [`push_opt`](ToRaw::push_opt) for each option and [`push_pos`](ToRaw::push_pos) for
+/// each positional argument, then the name and arguments of matched sub command:
+///
+/// ```ignore
+/// impl ToArgs for Clone {
+///     fn to_args(&self) -> Vec<OsString> {
+///         let mut args = vec![];
+///
+///         self.depth.push_opt("--depth", &mut args);
+///         self.repo.push_pos(&mut args);
+///         args
+///     }
+/// }
+/// ```
+pub trait ToArgs {
+    /// The arguments without the program name.
+    fn to_args(&self) -> Vec<OsString>;
+
+    /// The arguments quoted by [`join`](shell::join) without the program name.
+    ///
+    /// Return a failure if any argument is not valid UTF-8, such as a [`PathBuf`] on Unix.
+    fn to_line(&self) -> Result<String, Error> {
+        let args = self
+            .to_args()
+            .into_iter()
+            .map(|arg| {
+                arg.into_string().map_err(|arg| {
+                    fail!(
+                        "can not quote the argument `{}`: it is not valid UTF-8",
+                        arg.to_string_lossy()
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(shell::join(args))
+    }
+
+    /// The command line of program `name` quoted by [`join`](shell::join), the line can be
+    /// parsed by [`parse_str`](crate::ParserImpl::parse_str).
+    fn to_cmdline(&self, name: &str) -> Result<String, Error> {
+        let line = self.to_line()?;
+        let name = shell::join([name]);
+
+        Ok(if line.is_empty() {
+            name
+        } else {
+            format!("{} {}", name, line)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use super::*;
+    use crate::builder::Command;
+    use crate::builder::Handle;
+    use crate::infer::Infer;
+    use crate::prelude::Args;
+    use aopt::value::RawValParser;
+
+    /// Parse the option `--value` written by `value` and return the parsed value.
+    fn round_trip<T>(value: &T) -> Result<Option<T>, Error>
+    where
+        T: Infer + ToRaw,
+        T::Val: RawValParser,
+    {
+        let mut cmd = Command::new("tool");
+        let handle: Handle<T> = cmd.opt("--value").force(false);
+        let mut args = vec![OsString::from("tool")];
+
+        value.push_opt("--value", &mut args);
+        cmd.parse(Args::from(args))?;
+        Ok(handle.take())
+    }
+
+    fn assert_round_trip<T>(value: T) -> Result<(), Error>
+    where
+        T: Infer + ToRaw + PartialEq + Debug,
+        T::Val: RawValParser,
+    {
+        assert_eq!(round_trip(&value)?, Some(value));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_values() -> Result<(), Error> {
+        assert_round_trip(Radix::new(-0x7f_i32))?;
+        assert_round_trip(ByteSize::new(4096))?;
+        assert_round_trip(ByteSize::new(1001))?;
+        assert_round_trip(Duration::from_secs(90))?;
+        assert_round_trip(Duration::from_millis(1500))?;
+        assert_round_trip(Shell::Zsh)?;
+        assert_round_trip(Delimited::<String>::new(vec![
+            "a,b".to_owned(),
+            "c\\d".to_owned(),
+        ]))?;
+        assert_round_trip(Delimited::<PathBuf, ':'>::new(vec![
+            PathBuf::from("/usr/bin"),
+            PathBuf::from("/bin"),
+        ]))?;
+        assert_round_trip(Unique::new(BTreeSet::from([1u64, 2])))?;
+        assert_round_trip(VecDeque::from([1i64, -2]))?;
+        assert_round_trip(HashSet::from(["a".to_owned(), "-b".to_owned()]))?;
+        assert_round_trip((1u64, "-x".to_owned()))?;
+        assert_round_trip([255u8, 0, 0])?;
+        assert_round_trip(Multi::<u64, 1, 3>::new(vec![1, 2]))?;
+        assert_round_trip(Multi::<u64, 1, 3>::new(vec![1, 2, 3]))?;
+        assert_round_trip(10u64..20)?;
+        assert_round_trip(-5i64..=5)?;
+        assert_round_trip(10u64..)?;
+        assert_round_trip(..20u64)?;
+        assert_round_trip(..=20u64)?;
+        Ok(())
+    }
+
+    #[test]
+    fn write_dash_values_after_double_dash() {
+        let mut args = vec![];
+
+        "a".to_owned().push_pos(&mut args);
+        vec!["-x".to_owned(), "-y".to_owned()].push_pos(&mut args);
+        assert_eq!(args, ["a", "--", "-x", "-y"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reject_non_utf8_cmdline() {
+        use std::os::unix::ffi::OsStringExt;
+
+        struct Files(Vec<PathBuf>);
+
+        impl ToArgs for Files {
+            fn to_args(&self) -> Vec<OsString> {
+                let mut args = vec![];
+
+                self.0.push_pos(&mut args);
+                args
+            }
+        }
+
+        let files = Files(vec![PathBuf::from("a b")]);
+
+        assert_eq!(files.to_cmdline("tool").unwrap(), "tool 'a b'");
+        assert_eq!(files.to_line().unwrap(), "'a b'");
+
+        let files = Files(vec![PathBuf::from(OsString::from_vec(vec![0x66, 0xff]))]);
+
+        assert!(files.to_cmdline("tool").is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use std::ffi::OsStr;
+use std::ffi::OsString;
+use std::ops::RangeInclusive;
+
+use aopt::opt::Opt as _;
+use aopt::opt::Style;
+use aopt::set::Ctor;
+use aopt::set::Set;
+
+use crate::arity::like_option;
+use crate::arity::VALUE_SEPARATOR;
+use crate::opt::Opt;
+
+/// How an option consumes the arguments following it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Takes {
+    /// The boolean option, it can be combined with other flags such as `-ab` if `combined`.
+    Flag { combined: bool },
+
+    /// The option takes the next argument as its value if the value is not joined by `=`.
+    Value,
+
+    /// The option takes `min` to `max` values, the arguments following it are consumed until
+    /// an argument like option, see [`group_values`](crate::arity::group_values).
+    Values(usize, usize),
+
+    /// The sub command, the arguments after it belong to the sub command.
+    Cmd,
+}
+
+impl Takes {
+    pub fn new(styles: &[Style], arity: Option<&RangeInclusive<usize>>) -> Self {
+        if styles.contains(&Style::Cmd) {
+            Self::Cmd
+        } else if let Some(arity) = arity {
+            Self::Values(*arity.start(), *arity.end())
+        } else if styles.contains(&Style::Boolean) {
+            Self::Flag {
+                combined: styles.contains(&Style::Combined),
+            }
+        } else {
+            Self::Value
+        }
+    }
+
+    /// The minimum and maximum count of values.
+    pub fn value_range(&self) -> (usize, usize) {
+        match self {
+            Self::Flag { .. } | Self::Cmd => (0, 0),
+            Self::Value => (1, 1),
+            Self::Values(min, max) => (*min, *max),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum Token<'a, O> {
+    /// The option with the values joined by `=` or following it.
+    Opt {
+        opt: O,
+        name: &'a str,
+        values: Vec<&'a OsStr>,
+    },
+
+    /// The combined boolean flags, such as `-ab`.
+    Flags(Vec<O>),
+
+    /// The sub command.
+    Cmd(O),
+
+    /// The `--`, the arguments after it are positional arguments.
+    Stop,
+
+    /// The positional argument or the option not found.
+    Arg(&'a OsStr),
+}
+
+/// Split the arguments into options, values and positional arguments, it is shared by
+/// [`group_values`](crate::arity::group_values), [`env_values`](crate::env::env_values) and
+/// the [`Completion`](crate::completion::Completion) so they tokenize the same as each other.
+///
+/// The option is found by the callback of [`next_token`](Tokens::next_token), so the caller
+/// can switch to the options of sub command.
+#[derive(Debug)]
+pub(crate) struct Tokens<'a> {
+    args: &'a [OsString],
+    pos: usize,
+    stopped: bool,
+}
+
+impl<'a> Tokens<'a> {
+    /// Create the tokens of `args` without the program name.
+    pub fn new(args: &'a [OsString]) -> Self {
+        Self {
+            args,
+            pos: 0,
+            stopped: false,
+        }
+    }
+
+    /// Return `true` if the `--` is found.
+    pub fn stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// The next token and the arguments of it, `find` returns the option named `name` and
+    /// how it takes the arguments.
+    pub fn next_token<O, F>(&mut self, find: F) -> Option<(Token<'a, O>, &'a [OsString])>
+    where
+        F: Fn(&str) -> Option<(O, Takes)>,
+    {
+        let start = self.pos;
+        let arg = self.args.get(start)?;
+
+        self.pos += 1;
+
+        let token = match arg.to_str() {
+            _ if self.stopped => Token::Arg(arg),
+            Some("--") => {
+                self.stopped = true;
+                Token::Stop
+            }
+            Some(word) => self.word(word, find).unwrap_or(Token::Arg(arg)),
+            None => Token::Arg(arg),
+        };
+
+        Some((token, &self.args[start..self.pos]))
+    }
+
+    fn word<O, F>(&mut self, word: &'a str, find: F) -> Option<Token<'a, O>>
+    where
+        F: Fn(&str) -> Option<(O, Takes)>,
+    {
+        let (name, value) = match word.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (word, None),
+        };
+
+        if let Some((opt, takes)) = find(name) {
+            let values = match takes {
+                Takes::Cmd if value.is_some() => return None,
+                Takes::Cmd => return Some(Token::Cmd(opt)),
+                Takes::Flag { .. } => value.into_iter().map(OsStr::new).collect(),
+                Takes::Value => match value {
+                    Some(value) => vec![OsStr::new(value)],
+                    None => self.take_next(|_| true).into_iter().collect(),
+                },
+                Takes::Values(_, max) => {
+                    let mut values: Vec<_> = value
+                        .into_iter()
+                        .flat_map(|v| v.split(VALUE_SEPARATOR))
+                        .map(OsStr::new)
+                        .collect();
+                    // the values already joined, such as written by `ToRaw`, are not consumed again
+                    let grouped = values.len() > 1;
+
+                    while !grouped && values.len() < max {
+                        match self.take_next(|next| next.to_str().is_some_and(|v| !like_option(v)))
+                        {
+                            Some(next) => values.push(next),
+                            None => break,
+                        }
+                    }
+                    values
+                }
+            };
+
+            Some(Token::Opt { opt, name, values })
+        } else if value.is_none()
+            && word.len() > 2
+            && word.starts_with('-')
+            && !word.starts_with("--")
+        {
+            word[1..]
+                .chars()
+                .map(|ch| match find(&format!("-{}", ch)) {
+                    Some((opt, Takes::Flag { combined: true })) => Some(opt),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(Token::Flags)
+        } else {
+            None
+        }
+    }
+
+    fn take_next(&mut self, filter: impl Fn(&OsStr) -> bool) -> Option<&'a OsStr> {
+        let next = self.args.get(self.pos).filter(|v| filter(v))?;
+
+        self.pos += 1;
+        Some(next)
+    }
+}
+
+/// Find the option of `set` by name or alias, the positional arguments are not matched.
+pub(crate) fn find_in<'a, S>(set: &'a S) -> impl Fn(&str) -> Option<(&'a Opt, Takes)> + 'a
+where
+    S: Set,
+    S::Ctor: Ctor<Opt = Opt>,
+{
+    move |name| {
+        set.iter()
+            .filter(|opt| !opt.ignore_name())
+            .find(|opt| opt.name() == name || opt.mat_alias(name))
+            .map(|opt| (opt, Takes::new(opt.styles(), opt.arity())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aopt::opt::ConfigValue;
+
+    use super::*;
+    use crate::prelude::OptSet;
+    use crate::test_util::insert_opt;
+    use crate::value::Multi;
+    use crate::Error;
+
+    /// The tokens of `args` such as `--tag=a|b`, `-dv` and `[x]`.
+    fn tokens(set: &OptSet, args: &[&str]) -> Vec<String> {
+        let args: Vec<OsString> = args.iter().map(OsString::from).collect();
+        let find = find_in(set);
+        let mut tokens = Tokens::new(&args);
+        let mut ret = vec![];
+
+        while let Some((token, _)) = tokens.next_token(&find) {
+            ret.push(match token {
+                Token::Opt { opt, values, .. } => {
+                    let values: Vec<_> = values.iter().map(|v| v.to_string_lossy()).collect();
+
+                    format!("{}={}", opt.name(), values.join("|"))
+                }
+                Token::Flags(opts) => opts.iter().map(|v| v.name()).collect::<Vec<_>>().join(""),
+                Token::Cmd(opt) => format!("<{}>", opt.name()),
+                Token::Stop => "--".to_owned(),
+                Token::Arg(arg) => format!("[{}]", arg.to_string_lossy()),
+            });
+        }
+        ret
+    }
+
+    #[test]
+    fn split_tokens() -> Result<(), Error> {
+        let mut set = OptSet::default();
+
+        insert_opt::<bool>(&mut set, "-d", |_| {})?;
+        insert_opt::<bool>(&mut set, "-v", |_| {})?;
+        insert_opt::<Option<String>>(&mut set, "--cfg", |cfg| {
+            cfg.add_alias("-c");
+        })?;
+        insert_opt::<Option<Multi<String, 1, 2>>>(&mut set, "--tag", |_| {})?;
+
+        assert_eq!(
+            tokens(&set, &["-dv", "-c", "-d", "--tag", "a", "b", "c"]),
+            ["-d-v", "--cfg=-d", "--tag=a|b", "[c]"]
+        );
+        assert_eq!(
+            tokens(&set, &["--tag=a", "-v", "-dx", "--", "-d"]),
+            ["--tag=a", "-v=", "[-dx]", "--", "[-d]"]
+        );
+        Ok(())
+    }
+}
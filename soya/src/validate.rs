@@ -0,0 +1,200 @@
+use std::ffi::OsStr;
+use std::fmt::Debug;
+use std::ops::RangeBounds;
+use std::path::Path;
+
+use crate::infer::InferCtx;
+use crate::Error;
+
+/// Check the value after it parsed by the option, return the reason if the value is rejected.
+pub trait Validator<T: ?Sized> {
+    fn validate(&self, val: &T) -> Result<(), String>;
+}
+
+impl<T, F> Validator<T> for F
+where
+    T: ?Sized,
+    F: Fn(&T) -> Result<(), String>,
+{
+    fn validate(&self, val: &T) -> Result<(), String> {
+        (self)(val)
+    }
+}
+
+/// Run the `validator` on the value, the failure names the option and the raw input by `ctx`.
+///
+/// The error of `val` is passed through, it will be processed by [`Infer`](crate::infer::Infer).
+pub fn check<T, V>(ctx: &InferCtx, val: Result<T, Error>, validator: &V) -> Result<T, Error>
+where
+    V: Validator<T>,
+{
+    match val {
+        Ok(val) => match validator.validate(&val) {
+            Ok(()) => Ok(val),
+            Err(reason) => Err(ctx.failure(reason)),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/// The value must in the `range`, such as `range(1..=65535)` or `range(0.0..)`.
+pub fn range<T, R>(range: R) -> impl Fn(&T) -> Result<(), String>
+where
+    T: PartialOrd + ?Sized,
+    R: RangeBounds<T> + Debug,
+{
+    move |val| {
+        if range.contains(val) {
+            Ok(())
+        } else {
+            Err(format!("expected a value in range {:?}", range))
+        }
+    }
+}
+
+/// The value must not be empty.
+pub fn non_empty<T>() -> impl Fn(&T) -> Result<(), String>
+where
+    T: AsRef<OsStr> + ?Sized,
+{
+    |val| {
+        if val.as_ref().is_empty() {
+            Err("expected a non-empty value".to_owned())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The value must start with `prefix`.
+pub fn prefix<T>(prefix: &'static str) -> impl Fn(&T) -> Result<(), String>
+where
+    T: AsRef<OsStr> + ?Sized,
+{
+    move |val| {
+        if val.as_ref().to_string_lossy().starts_with(prefix) {
+            Ok(())
+        } else {
+            Err(format!("expected a value starts with `{}`", prefix))
+        }
+    }
+}
+
+/// The value must end with `suffix`.
+pub fn suffix<T>(suffix: &'static str) -> impl Fn(&T) -> Result<(), String>
+where
+    T: AsRef<OsStr> + ?Sized,
+{
+    move |val| {
+        if val.as_ref().to_string_lossy().ends_with(suffix) {
+            Ok(())
+        } else {
+            Err(format!("expected a value ends with `{}`", suffix))
+        }
+    }
+}
+
+/// The value must be an existing path.
+pub fn exists<T>() -> impl Fn(&T) -> Result<(), String>
+where
+    T: AsRef<Path> + ?Sized,
+{
+    |val| {
+        if val.as_ref().exists() {
+            Ok(())
+        } else {
+            Err("path does not exist".to_owned())
+        }
+    }
+}
+
+/// The value must be an existing file.
+pub fn is_file<T>() -> impl Fn(&T) -> Result<(), String>
+where
+    T: AsRef<Path> + ?Sized,
+{
+    |val| {
+        if val.as_ref().is_file() {
+            Ok(())
+        } else {
+            Err("not a file".to_owned())
+        }
+    }
+}
+
+/// The value must be an existing directory.
+pub fn is_dir<T>() -> impl Fn(&T) -> Result<(), String>
+where
+    T: AsRef<Path> + ?Sized,
+{
+    |val| {
+        if val.as_ref().is_dir() {
+            Ok(())
+        } else {
+            Err("not a directory".to_owned())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_built_in_validators() {
+        let port = range(1..=65535);
+
+        assert_eq!(port.validate(&443), Ok(()));
+        assert_eq!(
+            port.validate(&0),
+            Err("expected a value in range 1..=65535".to_owned())
+        );
+        assert!(range(0.0..).validate(&-1.0).is_err());
+        assert_eq!(non_empty().validate("x"), Ok(()));
+        assert_eq!(
+            non_empty().validate(""),
+            Err("expected a non-empty value".to_owned())
+        );
+        assert_eq!(prefix("http").validate("https://a"), Ok(()));
+        assert!(prefix("http").validate("ftp://a").is_err());
+        assert_eq!(suffix(".rs").validate("lib.rs"), Ok(()));
+        assert!(suffix(".rs").validate("lib.c").is_err());
+    }
+
+    #[test]
+    fn check_path_validators() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let file = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let missing = concat!(env!("CARGO_MANIFEST_DIR"), "/missing");
+
+        assert_eq!(exists().validate(file), Ok(()));
+        assert_eq!(
+            exists().validate(missing),
+            Err("path does not exist".to_owned())
+        );
+        assert_eq!(is_file().validate(file), Ok(()));
+        assert_eq!(is_file().validate(dir), Err("not a file".to_owned()));
+        assert_eq!(is_dir().validate(dir), Ok(()));
+        assert_eq!(is_dir().validate(file), Err("not a directory".to_owned()));
+    }
+
+    #[test]
+    fn report_rejected_value() {
+        let ctx = InferCtx::new("port")
+            .with_alias(Some("-p".to_owned()))
+            .with_raw(Some("0".into()))
+            .with_value_name("<u16>");
+        let port = range(1..=65535u16);
+
+        assert_eq!(check(&ctx, Ok(8080), &port).ok(), Some(8080));
+
+        let err = check(&ctx, Ok(0), &port).unwrap_err().to_string();
+
+        assert!(err.contains("invalid value `0` for `-p <u16>`"), "{}", err);
+        assert!(
+            err.contains("expected a value in range 1..=65535"),
+            "{}",
+            err
+        );
+    }
+}
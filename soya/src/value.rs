@@ -0,0 +1,908 @@
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::num::IntErrorKind;
+use std::num::ParseIntError;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::str::FromStr;
+use std::time::Duration;
+
+use aopt::ctx::Ctx;
+use aopt::prelude::Action;
+use aopt::prelude::ConfigValue;
+use aopt::value::raw2str;
+use aopt::value::RawValParser;
+
+use crate::arity::split_values;
+use crate::err::fail;
+use crate::infer::short_type_name;
+use crate::infer::Infer;
+use crate::infer::InferCtx;
+use crate::infer::OptSpec;
+use crate::Error;
+
+/// Create the failure of value `val`, the `reason` is kept as the cause so that
+/// [`InferCtx::wrap`](crate::infer::InferCtx::wrap) can name the option later.
+pub fn invalid_value(val: &str, reason: impl Display) -> Error {
+    let reason = reason.to_string();
+
+    fail!("invalid value `{}`: {}", val, reason).cause_by(fail!("{}", reason))
+}
+
+/// Parse the value with [`FromStr`], the message of `T::Err` is kept in the failure.
+pub fn from_str<T>(val: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    val.parse::<T>().map_err(|e| invalid_value(val, e))
+}
+
+/// Convert the value with [`TryFrom<String>`], the message of `T::Error` is kept in the failure.
+pub fn try_from<T>(val: String) -> Result<T, Error>
+where
+    T: TryFrom<String>,
+    T::Error: Display,
+{
+    let raw = val.clone();
+
+    T::try_from(val).map_err(|e| invalid_value(&raw, e))
+}
+
+/// Parse duration such as `30s`, `1.5h` or `2m30s`, a number without unit means seconds.
+///
+/// The supported units are `d`, `h`, `m`/`min`, `s`, `ms`, `us`/`µs` and `ns`.
+pub fn parse_duration(val: &str) -> Result<Duration, Error> {
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+    let invalid = |hint: &str| invalid_value(val, hint);
+    let mut rest = val.trim();
+    let mut total: u128 = 0;
+
+    if rest.is_empty() {
+        return Err(invalid("expected a duration such as `30s` or `2m30s`"));
+    }
+    if rest.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        // a bare number is number of seconds
+        return parse_duration(&format!("{}s", rest));
+    }
+    while !rest.is_empty() {
+        let num_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let (num, tail) = rest.split_at(num_len);
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        let scale: u128 = match unit.trim() {
+            "d" => 24 * 3600 * NANOS_PER_SEC,
+            "h" => 3600 * NANOS_PER_SEC,
+            "m" | "min" => 60 * NANOS_PER_SEC,
+            "s" => NANOS_PER_SEC,
+            "ms" => 1_000_000,
+            "us" | "µs" => 1_000,
+            "ns" => 1,
+            "" => return Err(invalid("missing unit after number")),
+            unit => return Err(invalid(&format!("unknown duration unit `{}`", unit))),
+        };
+        let (int, frac) = num.split_once('.').unwrap_or((num, ""));
+
+        if int.is_empty() && frac.is_empty() {
+            return Err(invalid(&format!("missing number before `{}`", unit)));
+        }
+        let int = if int.is_empty() {
+            0
+        } else {
+            int.parse::<u128>()
+                .map_err(|_| invalid("duration is too large"))?
+        };
+        // drop the digits beyond the precision of nanosecond
+        let frac = &frac[..frac.len().min(18)];
+        let frac = if frac.is_empty() {
+            0
+        } else {
+            let digits = frac
+                .parse::<u128>()
+                .map_err(|_| invalid(&format!("invalid number `{}`", num)))?;
+
+            digits * scale / 10u128.pow(frac.len() as u32)
+        };
+
+        total = int
+            .checked_mul(scale)
+            .and_then(|v| v.checked_add(frac))
+            .and_then(|v| v.checked_add(total))
+            .ok_or_else(|| invalid("duration is too large"))?;
+        rest = tail.trim_start();
+    }
+
+    let secs =
+        u64::try_from(total / NANOS_PER_SEC).map_err(|_| invalid("duration is too large"))?;
+
+    Ok(Duration::new(secs, (total % NANOS_PER_SEC) as u32))
+}
+
+/// Make any type implemented [`FromStr`] can be used as option value.
+///
+/// ```ignore
+/// // --ver 1.2.3
+/// ver: FromStrValue<semver::Version>,
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FromStrValue<T>(pub T);
+
+impl<T> FromStrValue<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for FromStrValue<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for FromStrValue<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Infer for FromStrValue<T>
+where
+    T: FromStr + 'static,
+    T::Err: Display,
+{
+    type Val = String;
+
+    fn infer_spec() -> OptSpec {
+        OptSpec::new::<Self>().with_value_name(Some(format!("<{}>", short_type_name::<T>())))
+    }
+
+    fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        from_str(&val?).map(Self)
+    }
+
+    fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+        self.0 = from_str(&val?)?;
+        Ok(())
+    }
+}
+
+/// Integer types can be parsed by [`Radix`].
+pub trait RadixInt: Sized + 'static {
+    const TYPE_NAME: &'static str;
+
+    fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError>;
+}
+
+macro_rules! impl_radix_int {
+    ($int:ty) => {
+        impl RadixInt for $int {
+            const TYPE_NAME: &'static str = stringify!($int);
+
+            fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError> {
+                <$int>::from_str_radix(src, radix)
+            }
+        }
+    };
+}
+
+impl_radix_int!(i8);
+impl_radix_int!(i16);
+impl_radix_int!(i32);
+impl_radix_int!(i64);
+impl_radix_int!(i128);
+impl_radix_int!(isize);
+impl_radix_int!(u8);
+impl_radix_int!(u16);
+impl_radix_int!(u32);
+impl_radix_int!(u64);
+impl_radix_int!(u128);
+impl_radix_int!(usize);
+
+/// Parse the integer with optional radix prefix `0x`, `0o` or `0b`, the `_` between digits are ignored.
+///
+/// The error is a hint describing why the value is invalid.
+pub fn parse_radix<T: RadixInt>(val: &str) -> Result<T, String> {
+    let (sign, digits) = match val.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", val.strip_prefix('+').unwrap_or(val)),
+    };
+    let prefix = digits.get(..2).map(|v| v.to_ascii_lowercase());
+    let (radix, digits) = match prefix.as_deref() {
+        Some("0x") => (16, &digits[2..]),
+        Some("0o") => (8, &digits[2..]),
+        Some("0b") => (2, &digits[2..]),
+        _ => (10, digits),
+    };
+
+    if digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+        return Err("`_` can only be used between digits".to_owned());
+    }
+    let digits = digits.replace('_', "");
+
+    if digits.is_empty() {
+        return Err("missing digits".to_owned());
+    }
+    if digits.starts_with(['+', '-']) {
+        return Err("unexpected sign after prefix".to_owned());
+    }
+    T::from_str_radix(&format!("{}{}", sign, digits), radix).map_err(|e| match e.kind() {
+        IntErrorKind::PosOverflow => format!("number too large to fit in {}", T::TYPE_NAME),
+        IntErrorKind::NegOverflow => format!("number too small to fit in {}", T::TYPE_NAME),
+        _ => format!("not a valid {} in radix {}", T::TYPE_NAME, radix),
+    })
+}
+
+/// Integer accept radix prefixes `0x`, `0o`, `0b` and `_` separators, such as `0x7fff_0000`.
+///
+/// ```ignore
+/// // --mode 0o755
+/// mode: Radix<u32>,
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Radix<T>(pub T);
+
+impl<T> Radix<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Radix<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Radix<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: RadixInt> FromStr for Radix<T> {
+    type Err = Error;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        parse_radix(val)
+            .map(Self)
+            .map_err(|e| invalid_value(val, e))
+    }
+}
+
+impl<T: RadixInt> RawValParser for Radix<T> {
+    type Error = Error;
+
+    fn parse(raw: Option<&OsStr>, ctx: &Ctx) -> Result<Self, Self::Error> {
+        let val = raw2str(raw)?;
+
+        // name the option in the failure even if the value is not parsed through `Infer`
+        parse_radix(val)
+            .map(Self)
+            .map_err(|e| match InferCtx::from_matched::<Self>(ctx) {
+                Ok(infer_ctx) => infer_ctx.failure(e),
+                Err(_) => invalid_value(val, e),
+            })
+    }
+}
+
+impl<T: RadixInt> Infer for Radix<T> {
+    type Val = Radix<T>;
+
+    fn infer_spec() -> OptSpec {
+        OptSpec::new::<Self>().with_value_name(Some(format!("<{}>", T::TYPE_NAME)))
+    }
+
+    fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        val
+    }
+
+    fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+        *self = val?;
+        Ok(())
+    }
+}
+
+/// A byte count parsed from number with SI or IEC unit, such as `512`, `4k`, `10MiB` or `1.5GB`.
+///
+/// SI units (`k`, `M`, `G`, `T`, `P`, `E`) are power of 1000 and IEC units (`Ki`, `Mi`, `Gi`, `Ti`, `Pi`, `Ei`)
+/// are power of 1024, the suffix `B` is optional and the unit is case insensitive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    const UNITS: [(&'static str, u64); 12] = [
+        ("EiB", 1 << 60),
+        ("EB", 1_000_000_000_000_000_000),
+        ("PiB", 1 << 50),
+        ("PB", 1_000_000_000_000_000),
+        ("TiB", 1 << 40),
+        ("TB", 1_000_000_000_000),
+        ("GiB", 1 << 30),
+        ("GB", 1_000_000_000),
+        ("MiB", 1 << 20),
+        ("MB", 1_000_000),
+        ("KiB", 1 << 10),
+        ("kB", 1_000),
+    ];
+
+    pub fn new(bytes: u64) -> Self {
+        Self(bytes)
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.0
+    }
+
+    fn unit_scale(unit: &str) -> Option<u64> {
+        let unit = unit.to_ascii_lowercase();
+        let unit = unit.strip_suffix('b').unwrap_or(&unit);
+
+        if unit.is_empty() {
+            return Some(1);
+        }
+        Self::UNITS
+            .iter()
+            .find(|(name, _)| name[..name.len() - 1].eq_ignore_ascii_case(unit))
+            .map(|(_, scale)| *scale)
+    }
+}
+
+impl Deref for ByteSize {
+    type Target = u64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(value: ByteSize) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = Error;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        let invalid = |hint: &str| invalid_value(val, hint);
+        let trimmed = val.trim();
+        let num_len = trimmed
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(trimmed.len());
+        let (num, unit) = trimmed.split_at(num_len);
+        let (int, frac) = num.split_once('.').unwrap_or((num, ""));
+
+        if int.is_empty() && frac.is_empty() {
+            return Err(invalid("expected a size such as `512`, `4k` or `10MiB`"));
+        }
+        let scale = Self::unit_scale(unit.trim())
+            .ok_or_else(|| invalid(&format!("unknown size unit `{}`", unit.trim())))?;
+        let int = if int.is_empty() {
+            0
+        } else {
+            int.parse::<u128>()
+                .map_err(|_| invalid("size is too large"))?
+        };
+        let frac = &frac[..frac.len().min(18)];
+        let frac = if frac.is_empty() {
+            0
+        } else {
+            let digits = frac
+                .parse::<u128>()
+                .map_err(|_| invalid(&format!("invalid number `{}`", num)))?;
+
+            digits * scale as u128 / 10u128.pow(frac.len() as u32)
+        };
+
+        int.checked_mul(scale as u128)
+            .and_then(|v| v.checked_add(frac))
+            .and_then(|v| u64::try_from(v).ok())
+            .map(Self)
+            .ok_or_else(|| invalid("size is too large"))
+    }
+}
+
+/// Display the size with the largest unit which divides it exactly, so it can be parsed back.
+impl Display for ByteSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bytes = self.0;
+
+        if bytes > 0 {
+            for (name, scale) in Self::UNITS {
+                if bytes % scale == 0 {
+                    return write!(f, "{}{}", bytes / scale, name);
+                }
+            }
+        }
+        write!(f, "{}B", bytes)
+    }
+}
+
+impl Infer for ByteSize {
+    type Val = String;
+
+    fn infer_spec() -> OptSpec {
+        OptSpec::new::<Self>().with_value_name(Some("<ByteSize>".to_owned()))
+    }
+
+    fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        val?.parse()
+    }
+
+    fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+        *self = Self::infer_map(val)?;
+        Ok(())
+    }
+}
+
+/// Split the value with `SEP`, the `SEP` can be escaped by `\`.
+pub fn split_escaped(val: &str, sep: char) -> Vec<String> {
+    let mut ret = vec![];
+    let mut item = String::new();
+    let mut chars = val.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            match chars.next() {
+                Some(next) if next == sep || next == '\\' => item.push(next),
+                Some(next) => {
+                    item.push(ch);
+                    item.push(next);
+                }
+                None => item.push(ch),
+            }
+        } else if ch == sep {
+            ret.push(std::mem::take(&mut item));
+        } else {
+            item.push(ch);
+        }
+    }
+    ret.push(item);
+    ret
+}
+
+/// Split a single argument into multiple values, such as `--features a,b,c`.
+///
+/// The separator can be escaped by `\`, and values of repeated occurrences are appended.
+///
+/// ```ignore
+/// // --features a,b --features c
+/// features: Delimited<String>,
+///
+/// // --path /usr/bin:/bin
+/// path: Delimited<PathBuf, ':'>,
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Delimited<T, const SEP: char = ','>(pub Vec<T>);
+
+impl<T, const SEP: char> Delimited<T, SEP> {
+    pub fn new(values: Vec<T>) -> Self {
+        Self(values)
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T, const SEP: char> Deref for Delimited<T, SEP> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const SEP: char> DerefMut for Delimited<T, SEP> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, const SEP: char> IntoIterator for Delimited<T, SEP> {
+    type Item = T;
+
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T, const SEP: char> Delimited<T, SEP>
+where
+    T: Infer,
+    T::Val: FromStr,
+    <T::Val as FromStr>::Err: Display,
+{
+    fn split(val: Result<String, Error>) -> Result<Vec<T>, Error> {
+        split_escaped(&val?, SEP)
+            .into_iter()
+            .map(|item| T::infer_map(from_str::<T::Val>(&item)))
+            .collect()
+    }
+}
+
+impl<T, const SEP: char> Infer for Delimited<T, SEP>
+where
+    T: Infer,
+    T::Val: FromStr,
+    <T::Val as FromStr>::Err: Display,
+{
+    type Val = String;
+
+    fn infer_spec() -> OptSpec {
+        let values = <T as Infer>::infer_spec()
+            .possible_values()
+            .map(<[String]>::to_vec);
+
+        OptSpec::new::<Self>()
+            .with_action(Action::App)
+            .with_possible_values(values)
+            .with_value_name(Some(format!("<{}>{}...", short_type_name::<T>(), SEP)))
+    }
+
+    fn infer_new() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Some(Self(vec![]))
+    }
+
+    fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        Self::split(val).map(Self)
+    }
+
+    fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+        self.0.extend(Self::split(val)?);
+        Ok(())
+    }
+}
+
+/// A set which reports a failure on duplicate values instead of ignoring them.
+///
+/// ```ignore
+/// // --exclude a --exclude a => error
+/// exclude: Unique<HashSet<String>>,
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Unique<S>(pub S);
+
+impl<S> Unique<S> {
+    pub fn new(value: S) -> Self {
+        Self(value)
+    }
+
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+
+impl<S> Deref for Unique<S> {
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S> DerefMut for Unique<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Implement [`Infer`] for `Unique<$set<T>>`, forward everything to `$set<T>` except the duplicate check.
+macro_rules! impl_unique_for {
+    ($set:ident $(, $bound:path)*) => {
+        impl<T: Infer + Debug $(+ $bound)*> Infer for Unique<$set<T>> {
+            type Val = <$set<T> as Infer>::Val;
+
+            fn infer_spec() -> OptSpec {
+                <$set<T> as Infer>::infer_spec()
+            }
+
+            fn infer_new() -> Option<Self>
+            where
+                Self: Sized,
+            {
+                <$set<T> as Infer>::infer_new().map(Self)
+            }
+
+            fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+            where
+                Self: Sized,
+            {
+                <$set<T> as Infer>::infer_map(val).map(Self)
+            }
+
+            fn infer_map_with(ctx: &InferCtx, val: Result<Self::Val, Error>) -> Result<Self, Error>
+            where
+                Self: Sized,
+            {
+                <$set<T> as Infer>::infer_map_with(ctx, val).map(Self)
+            }
+
+            fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+                let value = T::infer_map(val)?;
+
+                if self.0.contains(&value) {
+                    Err(fail!("duplicate value {:?}", value))
+                } else {
+                    self.0.insert(value);
+                    Ok(())
+                }
+            }
+
+            fn infer_mut_with(&mut self, ctx: &InferCtx, val: Result<Self::Val, Error>) -> Result<(), Error> {
+                let value = T::infer_map_with(ctx, val)?;
+
+                if self.0.contains(&value) {
+                    Err(ctx.failure(format!("duplicate value {:?}", value)))
+                } else {
+                    self.0.insert(value);
+                    Ok(())
+                }
+            }
+
+            fn infer_tweak_info<C>(cfg: &mut C) -> Result<(), Error>
+            where
+                Self: Sized,
+                C: ConfigValue + Default,
+            {
+                <$set<T> as Infer>::infer_tweak_info(cfg)
+            }
+        }
+    };
+}
+
+impl_unique_for!(HashSet, Eq, Hash);
+impl_unique_for!(BTreeSet, Ord);
+
+/// The option consume `MIN` to `MAX` arguments, such as `--files a b c`.
+///
+/// ```ignore
+/// // --range 1 or --range 1 5
+/// range: Multi<u64, 1, 2>,
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Multi<T, const MIN: usize, const MAX: usize>(pub Vec<T>);
+
+impl<T, const MIN: usize, const MAX: usize> Multi<T, MIN, MAX> {
+    pub fn new(values: Vec<T>) -> Self {
+        Self(values)
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> Deref for Multi<T, MIN, MAX> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> DerefMut for Multi<T, MIN, MAX> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> Infer for Multi<T, MIN, MAX>
+where
+    T: Infer,
+    T::Val: FromStr,
+    <T::Val as FromStr>::Err: Display,
+{
+    type Val = String;
+
+    fn infer_spec() -> OptSpec {
+        let name = format!("<{}>", short_type_name::<T>());
+        let mut names = vec![name.clone(); MIN];
+
+        if MAX > MIN {
+            names.push(format!("[{}]...", name));
+        }
+        OptSpec::new::<Self>()
+            .with_value_name(Some(names.join(" ")))
+            .with_arity(Some(MIN..=MAX))
+    }
+
+    fn infer_map(val: Result<Self::Val, Error>) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        split_values(&val?, MIN, MAX)?
+            .into_iter()
+            .map(|item| T::infer_map(from_str::<T::Val>(item)))
+            .collect::<Result<Vec<T>, Error>>()
+            .map(Self)
+    }
+
+    fn infer_mut(&mut self, val: Result<Self::Val, Error>) -> Result<(), Error> {
+        *self = Self::infer_map(val)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_name_of_from_str() {
+        let spec = FromStrValue::<std::net::IpAddr>::infer_spec();
+
+        assert_eq!(spec.value_name(), Some("<IpAddr>"));
+    }
+
+    #[test]
+    fn parse_radix_prefix() {
+        assert_eq!(parse_radix::<u32>("0x7fff_0000"), Ok(0x7fff_0000));
+        assert_eq!(parse_radix::<u32>("0o755"), Ok(0o755));
+        assert_eq!(parse_radix::<u8>("0B1010"), Ok(0b1010));
+        assert_eq!(parse_radix::<i32>("-0x10"), Ok(-16));
+        assert_eq!(parse_radix::<i32>("+1_000"), Ok(1000));
+        assert_eq!(parse_radix::<i8>("-128"), Ok(-128));
+    }
+
+    #[test]
+    fn parse_radix_invalid() {
+        assert_eq!(
+            parse_radix::<u32>("0x_ff"),
+            Err("`_` can only be used between digits".to_owned())
+        );
+        assert_eq!(
+            parse_radix::<u32>("1__0"),
+            Err("`_` can only be used between digits".to_owned())
+        );
+        assert_eq!(parse_radix::<u32>("0x"), Err("missing digits".to_owned()));
+        assert_eq!(
+            parse_radix::<i32>("0x-1"),
+            Err("unexpected sign after prefix".to_owned())
+        );
+        assert_eq!(
+            parse_radix::<u8>("0x100"),
+            Err("number too large to fit in u8".to_owned())
+        );
+        assert_eq!(
+            parse_radix::<i8>("-129"),
+            Err("number too small to fit in i8".to_owned())
+        );
+        assert_eq!(
+            parse_radix::<u32>("0b12"),
+            Err("not a valid u32 in radix 2".to_owned())
+        );
+        assert_eq!(
+            parse_radix::<u32>("-1"),
+            Err("not a valid u32 in radix 10".to_owned())
+        );
+    }
+
+    #[test]
+    fn parse_byte_size() -> Result<(), Error> {
+        assert_eq!("512".parse::<ByteSize>()?, ByteSize(512));
+        assert_eq!("4k".parse::<ByteSize>()?, ByteSize(4000));
+        assert_eq!("4KiB".parse::<ByteSize>()?, ByteSize(4096));
+        assert_eq!("10mib".parse::<ByteSize>()?, ByteSize(10 << 20));
+        assert_eq!("1.5GB".parse::<ByteSize>()?, ByteSize(1_500_000_000));
+        assert_eq!(".5Ki".parse::<ByteSize>()?, ByteSize(512));
+        assert_eq!(" 2 B ".parse::<ByteSize>()?, ByteSize(2));
+        assert!("".parse::<ByteSize>().is_err());
+        assert!("4x".parse::<ByteSize>().is_err());
+        assert!("16EiB".parse::<ByteSize>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn value_name_of_byte_size() {
+        assert_eq!(ByteSize::infer_spec().value_name(), Some("<ByteSize>"));
+    }
+
+    #[test]
+    fn display_byte_size() -> Result<(), Error> {
+        assert_eq!(ByteSize(0).to_string(), "0B");
+        assert_eq!(ByteSize(1001).to_string(), "1001B");
+        assert_eq!(ByteSize(1000).to_string(), "1kB");
+        assert_eq!(ByteSize(4096).to_string(), "4KiB");
+        assert_eq!(ByteSize(3 << 30).to_string(), "3GiB");
+        for bytes in [0, 1, 1000, 1024, 1536, 1 << 60, u64::MAX] {
+            assert_eq!(
+                ByteSize(bytes).to_string().parse::<ByteSize>()?,
+                ByteSize(bytes)
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn split_escaped_separator() {
+        assert_eq!(split_escaped("a,b,c", ','), ["a", "b", "c"]);
+        assert_eq!(split_escaped(r"a\,b,c", ','), ["a,b", "c"]);
+        assert_eq!(split_escaped(r"a\\,b", ','), ["a\\", "b"]);
+        assert_eq!(split_escaped(r"a\nb:c\", ':'), [r"a\nb", "c\\"]);
+        assert_eq!(split_escaped(",a,", ','), ["", "a", ""]);
+        assert_eq!(split_escaped("", ','), [""]);
+    }
+
+    #[test]
+    fn append_delimited_values() -> Result<(), Error> {
+        let mut features = Delimited::<String>::infer_map(Ok("a,b".to_owned()))?;
+
+        features.infer_mut(Ok(r"c\,d".to_owned()))?;
+        assert_eq!(features.0, ["a", "b", "c,d"]);
+
+        let ports = Delimited::<u16, ':'>::infer_map(Ok("80:443".to_owned()))?;
+
+        assert_eq!(ports.0, [80, 443]);
+        assert!(Delimited::<u16>::infer_map(Ok("80,x".to_owned())).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_duplicate_unique_values() -> Result<(), Error> {
+        let mut exclude = Unique::<BTreeSet<String>>::infer_map(Ok("a".to_owned()))?;
+
+        exclude.infer_mut(Ok("b".to_owned()))?;
+        assert_eq!(exclude.0, BTreeSet::from(["a".to_owned(), "b".to_owned()]));
+
+        let error = exclude.infer_mut(Ok("a".to_owned())).unwrap_err();
+
+        assert!(
+            error.to_string().contains("duplicate value \"a\""),
+            "{}",
+            error
+        );
+        assert_eq!(exclude.len(), 2);
+
+        let mut ids = Unique::<HashSet<u64>>::infer_new().unwrap();
+
+        ids.infer_mut(Ok(1))?;
+        assert!(ids.infer_mut(Ok(1)).is_err());
+        Ok(())
+    }
+}
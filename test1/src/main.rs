@@ -1,3 +1,5 @@
+use std::ffi::OsString;
+
 use soya::aopt::opt::ConfigValue;
 use soya::aopt::set::Ctor;
 use soya::aopt::set::Set;
@@ -6,12 +8,13 @@ use soya::err::err;
 use soya::err::Error;
 use soya::prelude::*;
 
-#[derive(Debug)]
+/// The stupid content tracker.
+#[derive(Debug, PartialEq)]
 pub struct Git {
     // --debug
     debug: bool,
 
-    // --cfg name=value
+    // --cfg name=value, complete = complete_cfg
     cfg: Option<Vec<String>>,
 
     // clone
@@ -20,24 +23,55 @@ pub struct Git {
     add: Option<Add>,
 }
 
-#[derive(Debug)]
+/// Clone a repository into a new directory.
+// validate = check_clone
+// collect_failures
+// group = "layout", at most one of `--bare` and `--mirror`
+// stop
+#[derive(Debug, PartialEq)]
 pub struct Clone {
-    // --depth u64
+    // --depth u64, validate = soya::validate::range(1..), env = "GIT_CLONE_DEPTH"
     depth: Option<u64>,
 
-    // <repo>
+    // <repo>, validate = soya::validate::non_empty()
     repo: String,
 
     // [dir]
     dir: Option<String>,
+
+    // --bare, group = "layout"
+    bare: bool,
+
+    // --mirror, group = "layout"
+    mirror: bool,
 }
 
-#[derive(Debug)]
+/// Add file contents to the index.
+// stop
+#[derive(Debug, PartialEq)]
 pub struct Add {
     // <files>...
     files: Vec<String>,
 }
 
+fn check_clone(clone: &Clone) -> Result<(), Error> {
+    if clone.depth.is_some() && !clone.repo.contains("://") {
+        Err(soya::err::fail!(
+            "`--depth` only works with a remote url, but found `{}`",
+            clone.repo
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn complete_cfg(_: &str) -> Vec<String> {
+    ["user.name=", "user.email=", "core.editor="]
+        .iter()
+        .map(|v| v.to_string())
+        .collect()
+}
+
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
 
@@ -92,12 +126,14 @@ impl<'inv> ParserImpl<'inv> for Add {
 
                 cfg.set_name("files");
                 cfg.set_index(Index::range(Some(1), None));
-                cfg.set_force(true);
                 <Pos<Vec<String>> as Infer>::infer_fill_info(&mut cfg)?;
                 cfg
             };
 
-            parser.ctor_mut(&ctor_default)?.new_with(cfg)?
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <Pos<Vec<String>> as Infer>::infer_fill_opt(&mut opt)?;
+            opt
         };
         let option_1 = {
             let cfg = {
@@ -105,16 +141,33 @@ impl<'inv> ParserImpl<'inv> for Add {
 
                 cfg.set_name("-h");
                 cfg.add_alias("--help");
-                cfg.set_force(false);
                 <bool as Infer>::infer_fill_info(&mut cfg)?;
                 cfg
             };
 
-            parser.ctor_mut(&ctor_default)?.new_with(cfg)?
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <bool as Infer>::infer_fill_opt(&mut opt)?;
+            opt
+        };
+        let option_2 = {
+            let cfg = {
+                let mut cfg = SetCfg::<Self::Parser<'inv>>::default();
+
+                <Option<Stop> as Infer>::infer_fill_info(&mut cfg)?;
+                cfg
+            };
+
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <Option<Stop> as Infer>::infer_fill_opt(&mut opt)?;
+            opt.set_hidden(true);
+            opt
         };
 
         parser.insert(option_0);
         parser.insert(option_1);
+        parser.insert(option_2);
 
         Ok(())
     }
@@ -123,43 +176,90 @@ impl<'inv> ParserImpl<'inv> for Add {
         Ok(())
     }
 
+    fn about() -> &'static str {
+        "Add file contents to the index."
+    }
+
     fn parse(args: Args) -> Result<Self, Self::Error>
     where
         Self: 'inv + Sized,
     {
         let mut value_0 = <Pos<Vec<String>> as Infer>::infer_new();
         let mut value_help = <bool as Infer>::infer_new();
+        let mut value_stop = <Option<Stop> as Infer>::infer_new();
+        let groups = [];
+        let failures = Failures::new(<Self as ParserImpl<'_>>::collect_failures());
         let mut parser = <Self as ParserImpl<'_>>::into_parser()?;
         let mut policy = <Self as ParserImpl<'_>>::into_policy()?;
 
         parser
             .entry(0)?
-            .on(fetch_or_update_handler!(value_0, Pos<Vec<String>>))
+            .on(failures.collect(fetch_or_update_handler!(value_0, Pos<Vec<String>>)))
             .then(NullStore);
         parser
             .entry(1)?
-            .on(fetch_or_update_handler!(value_help, bool))
+            .on(failures.collect(fetch_or_update_handler!(value_help, bool)))
+            .then(NullStore);
+        parser
+            .entry(2)?
+            .on(failures.collect(fetch_or_update_handler!(value_stop, Option<Stop>)))
             .then(NullStore);
 
-        let mut ret = parser.parse_policy(args, &mut policy)?;
+        let args = failures
+            .ok(group_values(&parser, args.clone()))
+            .unwrap_or(args);
+        let args = env_values(&parser, args);
+        let cmd = args
+            .first()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut ret = parser.parse_policy(args.clone(), &mut policy)?;
+        let help = display_help(&parser, &cmd, &groups);
+        let relations = relation_failures(&parser, &args, &groups)?;
 
         drop(parser);
         drop(policy);
 
         if value_help == Some(true) {
-            // display help
-            println!("display help for ..");
+            println!("{}", help);
         }
 
         if let Some(error) = ret.take_failure() {
-            Err(error)
-        } else {
-            Ok(Self {
-                files: value_0
-                    .ok_or_else(|| err!("Failed get value of field files"))
-                    .map(|v| v.0)?,
-            })
+            failures.push(error);
+        }
+        for failure in relations {
+            failures.push(failure);
         }
+
+        // the value is incomplete if there are failures already
+        let value = if failures.is_empty() {
+            failures.ok((|| -> Result<Self, Self::Error> {
+                let value = Self {
+                    files: value_0
+                        .ok_or_else(|| err!("Failed get value of field files"))
+                        .map(|v| v.0)?,
+                };
+
+                value.validate()?;
+                Ok(value)
+            })())
+        } else {
+            None
+        };
+
+        failures
+            .into_result()
+            .map_err(|e| soya::failure::usage_failure(&cmd, e))?;
+        value.ok_or_else(|| err!("Failed get value of command {}", cmd))
+    }
+}
+
+impl ToArgs for Add {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = vec![];
+
+        self.files.push_pos(&mut args);
+        args
     }
 }
 
@@ -183,12 +283,15 @@ impl<'inv> ParserImpl<'inv> for Clone {
                 let mut cfg = SetCfg::<Self::Parser<'inv>>::default();
 
                 cfg.set_name("--depth");
-                cfg.set_force(false);
                 <Option<u64> as Infer>::infer_fill_info(&mut cfg)?;
                 cfg
             };
 
-            parser.ctor_mut(&ctor_default)?.new_with(cfg)?
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <Option<u64> as Infer>::infer_fill_opt(&mut opt)?;
+            opt.set_env("GIT_CLONE_DEPTH");
+            opt
         };
         let option_1 = {
             let cfg = {
@@ -196,12 +299,14 @@ impl<'inv> ParserImpl<'inv> for Clone {
 
                 cfg.set_name("repo");
                 cfg.set_index(Index::Forward(1));
-                cfg.set_force(true);
                 <Pos<String> as Infer>::infer_fill_info(&mut cfg)?;
                 cfg
             };
 
-            parser.ctor_mut(&ctor_default)?.new_with(cfg)?
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <Pos<String> as Infer>::infer_fill_opt(&mut opt)?;
+            opt
         };
         let option_2 = {
             let cfg = {
@@ -209,12 +314,14 @@ impl<'inv> ParserImpl<'inv> for Clone {
 
                 cfg.set_name("dir");
                 cfg.set_index(Index::Forward(2));
-                cfg.set_force(false);
                 <Pos<Option<String>> as Infer>::infer_fill_info(&mut cfg)?;
                 cfg
             };
 
-            parser.ctor_mut(&ctor_default)?.new_with(cfg)?
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <Pos<Option<String>> as Infer>::infer_fill_opt(&mut opt)?;
+            opt
         };
         let option_3 = {
             let cfg = {
@@ -222,18 +329,65 @@ impl<'inv> ParserImpl<'inv> for Clone {
 
                 cfg.set_name("-h");
                 cfg.add_alias("--help");
-                cfg.set_force(false);
                 <bool as Infer>::infer_fill_info(&mut cfg)?;
                 cfg
             };
 
-            parser.ctor_mut(&ctor_default)?.new_with(cfg)?
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <bool as Infer>::infer_fill_opt(&mut opt)?;
+            opt
+        };
+        let option_4 = {
+            let cfg = {
+                let mut cfg = SetCfg::<Self::Parser<'inv>>::default();
+
+                cfg.set_name("--bare");
+                <bool as Infer>::infer_fill_info(&mut cfg)?;
+                cfg
+            };
+
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <bool as Infer>::infer_fill_opt(&mut opt)?;
+            opt
+        };
+        let option_5 = {
+            let cfg = {
+                let mut cfg = SetCfg::<Self::Parser<'inv>>::default();
+
+                cfg.set_name("--mirror");
+                <bool as Infer>::infer_fill_info(&mut cfg)?;
+                cfg
+            };
+
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <bool as Infer>::infer_fill_opt(&mut opt)?;
+            opt
+        };
+        let option_6 = {
+            let cfg = {
+                let mut cfg = SetCfg::<Self::Parser<'inv>>::default();
+
+                <Option<Stop> as Infer>::infer_fill_info(&mut cfg)?;
+                cfg
+            };
+
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <Option<Stop> as Infer>::infer_fill_opt(&mut opt)?;
+            opt.set_hidden(true);
+            opt
         };
 
         parser.insert(option_0);
         parser.insert(option_1);
         parser.insert(option_2);
         parser.insert(option_3);
+        parser.insert(option_4);
+        parser.insert(option_5);
+        parser.insert(option_6);
 
         Ok(())
     }
@@ -242,6 +396,18 @@ impl<'inv> ParserImpl<'inv> for Clone {
         Ok(())
     }
 
+    fn about() -> &'static str {
+        "Clone a repository into a new directory."
+    }
+
+    fn collect_failures() -> bool {
+        true
+    }
+
+    fn validate(&self) -> Result<(), Self::Error> {
+        check_clone(self)
+    }
+
     fn parse(args: Args) -> Result<Self, Self::Error>
     where
         Self: 'inv + Sized,
@@ -250,49 +416,116 @@ impl<'inv> ParserImpl<'inv> for Clone {
         let mut value_1 = <Pos<String> as Infer>::infer_new();
         let mut value_2 = <Pos<Option<String>> as Infer>::infer_new();
         let mut value_help = <bool as Infer>::infer_new();
+        let mut value_4 = <bool as Infer>::infer_new();
+        let mut value_5 = <bool as Infer>::infer_new();
+        let mut value_stop = <Option<Stop> as Infer>::infer_new();
+        let groups = [Group::new("layout").with_opts(["--bare", "--mirror"])];
+        let failures = Failures::new(<Self as ParserImpl<'_>>::collect_failures());
         let mut parser = <Self as ParserImpl<'_>>::into_parser()?;
         let mut policy = <Self as ParserImpl<'_>>::into_policy()?;
 
         parser
             .entry(0)?
-            .on(fetch_or_update_handler!(value_0, Option<u64>))
+            .on(failures.collect(fetch_or_update_handler!(
+                value_0,
+                Option<u64>,
+                soya::validate::range(1..)
+            )))
             .then(NullStore);
         parser
             .entry(1)?
-            .on(fetch_or_update_handler!(value_1, Pos<String>))
+            .on(failures.collect(fetch_or_update_handler!(
+                value_1,
+                Pos<String>,
+                soya::validate::non_empty::<String>()
+            )))
             .then(NullStore);
         parser
             .entry(2)?
-            .on(fetch_or_update_handler!(value_2, Pos<Option<String>>))
+            .on(failures.collect(fetch_or_update_handler!(value_2, Pos<Option<String>>)))
             .then(NullStore);
         parser
             .entry(3)?
-            .on(fetch_or_update_handler!(value_help, bool))
+            .on(failures.collect(fetch_or_update_handler!(value_help, bool)))
+            .then(NullStore);
+        parser
+            .entry(4)?
+            .on(failures.collect(fetch_or_update_handler!(value_4, bool)))
+            .then(NullStore);
+        parser
+            .entry(5)?
+            .on(failures.collect(fetch_or_update_handler!(value_5, bool)))
+            .then(NullStore);
+        parser
+            .entry(6)?
+            .on(failures.collect(fetch_or_update_handler!(value_stop, Option<Stop>)))
             .then(NullStore);
 
-        let mut ret = parser.parse_policy(args, &mut policy)?;
+        let args = failures
+            .ok(group_values(&parser, args.clone()))
+            .unwrap_or(args);
+        let args = env_values(&parser, args);
+        let cmd = args
+            .first()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut ret = parser.parse_policy(args.clone(), &mut policy)?;
+        let help = display_help(&parser, &cmd, &groups);
+        let relations = relation_failures(&parser, &args, &groups)?;
 
         drop(parser);
         drop(policy);
 
         if value_help == Some(true) {
-            // display help
-            println!("display help for ..");
+            println!("{}", help);
         }
 
         if let Some(error) = ret.take_failure() {
-            Err(error)
-        } else {
-            Ok(Self {
-                depth: value_0.ok_or_else(|| err!("Failed get value of field depth"))?,
-                repo: value_1
-                    .ok_or_else(|| err!("Failed get value of field repo"))
-                    .map(|v| v.0)?,
-                dir: value_2
-                    .ok_or_else(|| err!("Failed get value of field dir"))
-                    .map(|v| v.0)?,
-            })
+            failures.push(error);
         }
+        for failure in relations {
+            failures.push(failure);
+        }
+
+        // the value is incomplete if there are failures already
+        let value = if failures.is_empty() {
+            failures.ok((|| -> Result<Self, Self::Error> {
+                let value = Self {
+                    depth: value_0.ok_or_else(|| err!("Failed get value of field depth"))?,
+                    repo: value_1
+                        .ok_or_else(|| err!("Failed get value of field repo"))
+                        .map(|v| v.0)?,
+                    dir: value_2
+                        .ok_or_else(|| err!("Failed get value of field dir"))
+                        .map(|v| v.0)?,
+                    bare: value_4.ok_or_else(|| err!("Failed get value of field bare"))?,
+                    mirror: value_5.ok_or_else(|| err!("Failed get value of field mirror"))?,
+                };
+
+                value.validate()?;
+                Ok(value)
+            })())
+        } else {
+            None
+        };
+
+        failures
+            .into_result()
+            .map_err(|e| soya::failure::usage_failure(&cmd, e))?;
+        value.ok_or_else(|| err!("Failed get value of command {}", cmd))
+    }
+}
+
+impl ToArgs for Clone {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = vec![];
+
+        self.depth.push_opt("--depth", &mut args);
+        self.bare.push_opt("--bare", &mut args);
+        self.mirror.push_opt("--mirror", &mut args);
+        self.repo.push_pos(&mut args);
+        self.dir.push_pos(&mut args);
+        args
     }
 }
 
@@ -316,24 +549,28 @@ impl<'inv> ParserImpl<'inv> for Git {
                 let mut cfg = SetCfg::<Self::Parser<'inv>>::default();
 
                 cfg.set_name("--debug");
-                cfg.set_force(false);
                 <bool as Infer>::infer_fill_info(&mut cfg)?;
                 cfg
             };
 
-            parser.ctor_mut(&ctor_default)?.new_with(cfg)?
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <bool as Infer>::infer_fill_opt(&mut opt)?;
+            opt
         };
         let option_1 = {
             let cfg = {
                 let mut cfg = SetCfg::<Self::Parser<'inv>>::default();
 
                 cfg.set_name("--cfg");
-                cfg.set_force(false);
                 <Option<Vec<String>> as Infer>::infer_fill_info(&mut cfg)?;
                 cfg
             };
 
-            parser.ctor_mut(&ctor_default)?.new_with(cfg)?
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <Option<Vec<String>> as Infer>::infer_fill_opt(&mut opt)?;
+            opt
         };
         let option_2 = {
             let cfg = {
@@ -344,7 +581,10 @@ impl<'inv> ParserImpl<'inv> for Git {
                 cfg
             };
 
-            parser.ctor_mut(&ctor_default)?.new_with(cfg)?
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <Cmd as Infer>::infer_fill_opt(&mut opt)?;
+            opt
         };
         let option_3 = {
             let cfg = {
@@ -355,7 +595,10 @@ impl<'inv> ParserImpl<'inv> for Git {
                 cfg
             };
 
-            parser.ctor_mut(&ctor_default)?.new_with(cfg)?
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
+
+            <Cmd as Infer>::infer_fill_opt(&mut opt)?;
+            opt
         };
         let option_4 = {
             let cfg = {
@@ -363,14 +606,15 @@ impl<'inv> ParserImpl<'inv> for Git {
 
                 cfg.set_name("-h");
                 cfg.add_alias("--help");
-                cfg.set_force(false);
                 <bool as Infer>::infer_fill_info(&mut cfg)?;
                 cfg
             };
 
-            parser.ctor_mut(&ctor_default)?.new_with(cfg)?
-        };
+            let mut opt = parser.ctor_mut(&ctor_default)?.new_with(cfg)?;
 
+            <bool as Infer>::infer_fill_opt(&mut opt)?;
+            opt
+        };
         parser.insert(option_0);
         parser.insert(option_1);
         parser.insert(option_2);
@@ -384,6 +628,20 @@ impl<'inv> ParserImpl<'inv> for Git {
         Ok(())
     }
 
+    fn about() -> &'static str {
+        "The stupid content tracker."
+    }
+
+    fn describe(name: &str) -> Result<CmdDesc, Self::Error> {
+        Ok(<Self as ParserImpl<'_>>::describe_opts(name)?
+            .with_sub(Clone::describe("clone")?)
+            .with_sub(Add::describe("add")?))
+    }
+
+    fn completion(name: &str) -> Result<Completion, Self::Error> {
+        Completion::new(&Self::describe(name)?).with_complete("--cfg", complete_cfg)
+    }
+
     fn parse(args: Args) -> Result<Self, Self::Error>
     where
         Self: 'inv + Sized,
@@ -393,63 +651,224 @@ impl<'inv> ParserImpl<'inv> for Git {
         let mut value_2 = None;
         let mut value_3 = None;
         let mut value_help = <bool as Infer>::infer_new();
+        let groups = [];
+        let failures = Failures::new(<Self as ParserImpl<'_>>::collect_failures());
         let mut parser = <Self as ParserImpl<'_>>::into_parser()?;
         let mut policy = <Self as ParserImpl<'_>>::into_policy()?;
 
         parser
             .entry(0)?
-            .on(fetch_or_update_handler!(value_0, bool))
+            .on(failures.collect(fetch_or_update_handler!(value_0, bool)))
             .then(NullStore);
         parser
             .entry(1)?
-            .on(fetch_or_update_handler!(value_1, Option<Vec<String>>))
+            .on(failures.collect(fetch_or_update_handler!(value_1, Option<Vec<String>>)))
             .then(NullStore);
         parser
             .entry(2)?
-            .on(|set, ctx| {
+            .on(|_set, ctx| {
                 let index = ctx.idx()?;
                 let mut args: Vec<_> = ctx.args().iter().map(|v| v.to_os_string()).collect();
 
-                args.remove(index);
-                value_2 = Clone::parse(Args::from(args)).ok();
+                let name = args.remove(index);
+
+                // keep the command path in the program name
+                args[0].push(" ");
+                args[0].push(name);
+                value_2 = Some(Clone::parse(Args::from(args)));
                 Ok(Some(()))
             })
             .then(NullStore);
         parser
             .entry(3)?
-            .on(|set, ctx| {
+            .on(|_set, ctx| {
                 let index = ctx.idx()?;
                 let mut args: Vec<_> = ctx.args().iter().map(|v| v.to_os_string()).collect();
 
-                args.remove(index);
-                value_3 = Add::parse(Args::from(args)).ok();
+                let name = args.remove(index);
+
+                // keep the command path in the program name
+                args[0].push(" ");
+                args[0].push(name);
+                value_3 = Some(Add::parse(Args::from(args)));
                 Ok(Some(()))
             })
             .then(NullStore);
         parser
             .entry(4)?
-            .on(fetch_or_update_handler!(value_help, bool))
+            .on(failures.collect(fetch_or_update_handler!(value_help, bool)))
             .then(NullStore);
 
-        let mut ret = parser.parse_policy(args, &mut policy)?;
+        let args = failures
+            .ok(group_values(&parser, args.clone()))
+            .unwrap_or(args);
+        let args = env_values(&parser, args);
+        let cmd = args
+            .first()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut ret = parser.parse_policy(args.clone(), &mut policy)?;
+        let help = display_help(&parser, &cmd, &groups);
+        let relations = relation_failures(&parser, &args, &groups)?;
 
         drop(parser);
         drop(policy);
 
         if value_help == Some(true) {
-            // display help
-            println!("display help for ..");
+            println!("{}", help);
         }
 
         if let Some(error) = ret.take_failure() {
-            Err(error)
+            failures.push(error);
+        }
+        for failure in relations {
+            failures.push(failure);
+        }
+
+        // the failure of sub command is reported with its own command path,
+        // after the failures of this command
+        let (value_2, error_2) = match value_2 {
+            Some(Err(e)) => (None, Some(e)),
+            value => (value.and_then(Result::ok), None),
+        };
+        let (value_3, error_3) = match value_3 {
+            Some(Err(e)) => (None, Some(e)),
+            value => (value.and_then(Result::ok), None),
+        };
+        // the value is incomplete if there are failures already
+        let value = if failures.is_empty() {
+            failures.ok((|| -> Result<Self, Self::Error> {
+                let value = Self {
+                    debug: value_0.ok_or_else(|| err!("Failed get value of field debug"))?,
+                    cfg: value_1.ok_or_else(|| err!("Failed get value of field cfg"))?,
+                    clone: value_2,
+                    add: value_3,
+                };
+
+                value.validate()?;
+                Ok(value)
+            })())
         } else {
-            Ok(Self {
-                debug: value_0.ok_or_else(|| err!("Failed get value of field debug"))?,
-                cfg: value_1.ok_or_else(|| err!("Failed get value of field cfg"))?,
-                clone: value_2,
-                add: value_3,
-            })
+            None
+        };
+
+        failures
+            .into_result()
+            .map_err(|e| soya::failure::usage_failure(&cmd, e))?;
+        if let Some(error) = error_2.or(error_3) {
+            return Err(error);
+        }
+        value.ok_or_else(|| err!("Failed get value of command {}", cmd))
+    }
+}
+
+impl ToArgs for Git {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = vec![];
+
+        self.debug.push_opt("--debug", &mut args);
+        self.cfg.push_opt("--cfg", &mut args);
+        if let Some(clone) = &self.clone {
+            args.push(OsString::from("clone"));
+            args.extend(clone.to_args());
         }
+        if let Some(add) = &self.add {
+            args.push(OsString::from("add"));
+            args.extend(add.to_args());
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_command_line_string() -> Result<(), Error> {
+        let clone = Clone::parse_str("clone --depth 1 'https://host/my repo' dir")?;
+
+        assert_eq!(clone.depth, Some(1));
+        assert_eq!(clone.repo, "https://host/my repo");
+        assert_eq!(clone.dir.as_deref(), Some("dir"));
+
+        let git = Git::parse_str(r#"git --debug add "a b" c\ d"#)?;
+
+        assert!(git.debug);
+        assert_eq!(git.add.expect("add is matched").files, ["a b", "c d"]);
+        Ok(())
+    }
+
+    #[test]
+    fn report_unbalanced_quote() {
+        let error = Git::parse_str("git clone 'my repo").unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("unterminated single quote in `git clone 'my repo`"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn generate_anywhere_before_double_dash() -> Result<(), Error> {
+        assert!(Git::generate(
+            "git",
+            &Args::from(["git", "--debug", "--generate-schema"])
+        )?);
+        assert!(Git::generate(
+            "git",
+            &Args::from(["git", "clone", "--generate-completion=zsh"])
+        )?);
+        assert!(!Git::generate(
+            "git",
+            &Args::from(["git", "add", "--", "--generate-schema"])
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_to_args() -> Result<(), Error> {
+        let add = Add {
+            files: vec![
+                "a b".to_owned(),
+                "-x".to_owned(),
+                "--".to_owned(),
+                "-".to_owned(),
+            ],
+        };
+        let clone = Clone {
+            depth: Some(1),
+            repo: "-r://host/repo".to_owned(),
+            dir: None,
+            bare: false,
+            mirror: true,
+        };
+        let git = Git {
+            debug: true,
+            cfg: Some(vec!["a=1".to_owned(), "b=-2".to_owned()]),
+            clone: None,
+            add: Some(Add {
+                files: vec!["a".to_owned()],
+            }),
+        };
+
+        assert_eq!(add.to_args(), ["a b", "--", "-x", "--", "-"]);
+        assert_eq!(Add::parse(Args::from(args_of("git add", &add)))?, add);
+        assert_eq!(
+            Clone::parse(Args::from(args_of("git clone", &clone)))?,
+            clone
+        );
+        assert_eq!(Git::parse(Args::from(args_of("git", &git)))?, git);
+        assert_eq!(Git::parse_str(&git.to_cmdline("git")?)?, git);
+        Ok(())
+    }
+
+    fn args_of(name: &str, value: &impl ToArgs) -> Vec<OsString> {
+        std::iter::once(OsString::from(name))
+            .chain(value.to_args())
+            .collect()
     }
 }
@@ -1,8 +1,334 @@
+use std::net::IpAddr;
+
+use soya_derive::Infer;
 use soya_derive::Soya;
+use soya_derive::SoyaValue;
+use soya_derive::ToArgs;
+
+/// The stupid content tracker.
+#[derive(Debug, PartialEq, Soya, ToArgs)]
+#[soya(policy = "pre")]
+pub struct Git {
+    /// Print the debug information
+    debug: bool,
+
+    /// Set the configuration `name=value`
+    #[arg(complete = complete_cfg)]
+    cfg: Option<Vec<String>>,
+
+    /// Trace the execution of git
+    #[arg(hide)]
+    trace: bool,
+
+    #[sub(name = "clone")]
+    clone: Option<GitClone>,
+
+    #[sub(name = "add")]
+    add: Option<GitAdd>,
+}
+
+/// Clone a repository into a new directory.
+#[derive(Debug, PartialEq, Soya, ToArgs)]
+#[soya(validate = check_clone, group("layout"))]
+pub struct GitClone {
+    /// Create a shallow clone with the history truncated
+    #[arg(validate = soya::validate::range(1..))]
+    depth: Option<u64>,
+
+    /// The repository to clone from
+    #[pos(validate = soya::validate::non_empty::<String>())]
+    repo: String,
+
+    /// The directory of new repository
+    #[pos]
+    dir: Option<String>,
+
+    /// Create a bare repository
+    #[arg(group = "layout")]
+    bare: bool,
+
+    /// Set up a mirror of the source repository
+    #[arg(group = "layout")]
+    mirror: bool,
+
+    /// Clone only the history leading to the tip of a single branch
+    #[arg(conflicts_with = "--mirror")]
+    single_branch: bool,
+
+    /// Check out the branch instead of the remote HEAD
+    #[arg(alias = "-b", required_if("--single-branch"), complete = complete_branch)]
+    branch: Option<String>,
+
+    /// Clone the submodules with a depth of 1
+    #[arg(requires = "--depth")]
+    shallow_submodules: bool,
+}
+
+/// Add file contents to the index.
+#[derive(Debug, PartialEq, Soya, ToArgs)]
+pub struct GitAdd {
+    /// Be verbose
+    #[arg(alias = "-v", env = "GIT_ADD_VERBOSE")]
+    verbose: bool,
 
-#[derive(Debug, Clone, Soya)]
-pub struct Cli {}
+    #[pos]
+    files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, SoyaValue)]
+pub enum Mode {
+    Fast,
+
+    Safe,
+
+    #[value(name = "auto", alias = "default")]
+    Automatic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Infer)]
+pub struct Port(u16);
+
+#[derive(Debug, Clone, PartialEq, Eq, Infer)]
+#[infer(from_str)]
+pub struct Peer(IpAddr);
+
+impl std::str::FromStr for Peer {
+    type Err = std::net::AddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Peer)
+    }
+}
+
+fn check_clone(clone: &GitClone) -> Result<(), soya::err::Error> {
+    if clone.depth.is_some() && !clone.repo.contains("://") {
+        Err(soya::err::fail!(
+            "`--depth` only works with a remote url, but found `{}`",
+            clone.repo
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn complete_cfg(_: &str) -> Vec<String> {
+    ["user.name=", "user.email=", "core.editor="]
+        .iter()
+        .map(|v| v.to_string())
+        .collect()
+}
+
+fn complete_branch(word: &str) -> Vec<String> {
+    ["main", "next"]
+        .iter()
+        .filter(|v| v.starts_with(word))
+        .map(|v| v.to_string())
+        .collect()
+}
 
 fn main() {
     println!("Hello, world!");
 }
+
+#[cfg(test)]
+mod tests {
+    use soya::dynamic::DynCommand;
+    use soya::infer::Infer;
+    use soya::prelude::Args;
+    use soya::to_args::ToArgs;
+    use soya::to_args::ToRaw;
+    use soya::ParserImpl;
+
+    use super::*;
+
+    fn parse<T>(args: &[&str]) -> Result<T, soya::err::Error>
+    where
+        T: for<'a> ParserImpl<'a, Error = soya::err::Error>,
+    {
+        T::parse(Args::from(args.to_vec()))
+    }
+
+    #[test]
+    fn parse_derived_command() -> Result<(), soya::err::Error> {
+        let git: Git = parse(&["git", "--debug", "--cfg", "a=1", "add", "a", "b"])?;
+
+        assert!(git.debug);
+        assert_eq!(git.cfg, Some(vec!["a=1".to_owned()]));
+        assert_eq!(git.clone, None);
+        assert_eq!(git.add.expect("add is matched").files, ["a", "b"]);
+
+        let clone: GitClone = parse(&["git clone", "--depth", "1", "https://host/repo"])?;
+
+        assert_eq!(
+            clone,
+            GitClone {
+                depth: Some(1),
+                repo: "https://host/repo".to_owned(),
+                dir: None,
+                bare: false,
+                mirror: false,
+                single_branch: false,
+                branch: None,
+                shallow_submodules: false,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hide_derived_option() -> Result<(), soya::err::Error> {
+        let git: Git = parse(&["git", "--trace", "add", "a"])?;
+        let desc = Git::describe("git")?;
+
+        assert!(git.trace);
+        assert!(desc.opts().iter().any(|v| v.name() == "--debug"));
+        assert!(!desc.opts().iter().any(|v| v.name() == "--trace"));
+        Ok(())
+    }
+
+    #[test]
+    fn complete_derived_options() -> Result<(), soya::err::Error> {
+        let completion = Git::completion("git")?;
+
+        assert_eq!(
+            completion.complete(&["git", "--cfg", "user."]),
+            ["user.email=", "user.name="]
+        );
+        assert_eq!(
+            completion.complete(&["git", "clone", "--branch", "m"]),
+            ["main"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn read_derived_env() -> Result<(), soya::err::Error> {
+        std::env::set_var("GIT_ADD_VERBOSE", "1");
+
+        let add: GitAdd = parse(&["git add", "a"])?;
+        let desc = GitAdd::describe("add")?;
+
+        std::env::remove_var("GIT_ADD_VERBOSE");
+        assert!(add.verbose);
+        assert!(desc
+            .opts()
+            .iter()
+            .any(|v| v.name() == "--verbose" && v.env() == Some("GIT_ADD_VERBOSE")));
+        Ok(())
+    }
+
+    #[test]
+    fn validate_derived_fields() {
+        let error = parse::<GitClone>(&["git clone", "--depth", "0", "repo"]).unwrap_err();
+
+        assert!(error.to_string().contains("`--depth"), "{}", error);
+        assert!(error.to_string().contains("`0`"), "{}", error);
+
+        let error = parse::<GitClone>(&["git clone", ""]).unwrap_err();
+
+        assert!(error.to_string().contains("`<repo>`"), "{}", error);
+    }
+
+    #[test]
+    fn check_derived_relations() {
+        let failure = |args: &[&str]| parse::<GitClone>(args).unwrap_err().to_string();
+
+        assert!(failure(&["git clone", "--bare", "--mirror", "repo"])
+            .contains("option `--bare` cannot be used with `--mirror` of group `layout`"),);
+        assert!(failure(&[
+            "git clone",
+            "--single-branch",
+            "--mirror",
+            "-b",
+            "main",
+            "repo"
+        ])
+        .contains("option `--single-branch` cannot be used with `--mirror`"));
+        assert!(failure(&["git clone", "--single-branch", "repo"])
+            .contains("option `--branch` is required when `--single-branch` is present"));
+        assert!(failure(&["git clone", "--shallow-submodules", "repo"])
+            .contains("option `--shallow-submodules` requires `--depth`"));
+    }
+
+    #[test]
+    fn validate_derived_command() {
+        let error = parse::<GitClone>(&["git clone", "--depth", "1", "repo"]).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "git clone: `--depth` only works with a remote url, but found `repo`\n\n\
+             For more information, try `git clone --help`"
+        );
+    }
+
+    #[test]
+    fn round_trip_derived_command() -> Result<(), soya::err::Error> {
+        let git = Git {
+            debug: true,
+            cfg: Some(vec!["user.name=a b".to_owned()]),
+            trace: false,
+            clone: None,
+            add: Some(GitAdd {
+                verbose: false,
+                files: vec!["a".to_owned()],
+            }),
+        };
+        let mut args = vec![std::ffi::OsString::from("git")];
+
+        args.extend(git.to_args());
+        assert_eq!(Git::parse(Args::from(args))?, git);
+
+        let clone = GitClone {
+            depth: Some(1),
+            repo: "https://host/repo".to_owned(),
+            dir: Some("-dir".to_owned()),
+            bare: true,
+            mirror: false,
+            single_branch: true,
+            branch: Some("main".to_owned()),
+            shallow_submodules: true,
+        };
+        let mut args = vec![std::ffi::OsString::from("git clone")];
+
+        args.extend(clone.to_args());
+        assert_eq!(GitClone::parse(Args::from(args))?, clone);
+
+        let add = GitAdd {
+            verbose: true,
+            files: vec!["a".to_owned(), "-b".to_owned()],
+        };
+
+        assert_eq!(add.to_line()?, "--verbose a -- -b");
+        assert_eq!(
+            parse::<GitAdd>(&["git add", "--verbose", "a", "--", "-b"])?,
+            add
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn load_derived_schema() -> Result<(), soya::err::Error> {
+        let git = DynCommand::from_schema(&Git::schema("git")?.render())?;
+        let values = git.parse(Args::from(["git", "--debug", "add", "a", "b"]))?;
+        let (name, add) = values.sub().expect("add is matched");
+
+        assert_eq!(values.get_bool("--debug"), Some(true));
+        assert_eq!(name, "add");
+        assert_eq!(
+            add.get_list("files"),
+            Some(&["a".to_owned(), "b".to_owned()][..])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_value_enum() -> Result<(), soya::err::Error> {
+        for mode in [Mode::Fast, Mode::Safe, Mode::Automatic] {
+            let raw = mode.to_raw().remove(0).into_string().unwrap();
+
+            assert_eq!(Mode::infer_map(Ok(raw))?, mode);
+        }
+        assert_eq!(Mode::Automatic.to_raw(), ["auto"]);
+        Ok(())
+    }
+}